@@ -0,0 +1,29 @@
+import { Point } from "./point.js";
+
+export class ColoredPoint {
+  constructor({ point, hex }) {
+    this.point = point;
+    this.hex = hex;
+  }
+
+  toJSON() {
+    return { point: this.point, hex: this.hex };
+  }
+
+  static fromObject(object) {
+    if (object == null) {
+      return null;
+    }
+    return new ColoredPoint({
+      point: Point.fromObject(object.point),
+      hex: object.hex,
+    });
+  }
+
+  static fromArray(array) {
+    if (array == null) {
+      return [];
+    }
+    return array.filter((object) => object != null).map(ColoredPoint.fromObject);
+  }
+}
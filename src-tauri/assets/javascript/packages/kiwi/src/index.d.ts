@@ -0,0 +1,287 @@
+export interface PointLike {
+  x: number;
+  y: number;
+}
+
+export declare class Point {
+  x: number;
+  y: number;
+  constructor(point: PointLike);
+  toJSON(): PointLike;
+  static fromObject(object: PointLike | null | undefined): Point | null;
+}
+
+export interface RgbOffsetLike {
+  r: number;
+  g: number;
+  b: number;
+}
+
+export declare class RgbOffset {
+  r: number;
+  g: number;
+  b: number;
+  constructor(offset: RgbOffsetLike);
+  toJSON(): RgbOffsetLike;
+  static fromObject(object: RgbOffsetLike | null | undefined): RgbOffset | null;
+}
+
+export declare class ColoredPoint {
+  point: Point;
+  hex: string;
+  constructor(coloredPoint: { point: PointLike; hex: string });
+  toJSON(): { point: PointLike; hex: string };
+  static fromObject(object: unknown): ColoredPoint | null;
+  static fromArray(array: unknown[] | null | undefined): ColoredPoint[];
+}
+
+export declare class WeightPoint {
+  point: Point;
+  weight: number;
+  constructor(weightPoint: { point: PointLike; weight: number });
+  toJSON(): { point: PointLike; weight: number };
+  static fromObject(object: unknown): WeightPoint | null;
+  static fromArray(array: unknown[] | null | undefined): WeightPoint[];
+}
+
+export declare class Response<T = any> {
+  status: "success" | "error";
+  message: string | null;
+  data: T | null;
+  constructor(response: { status: "success" | "error"; message: string | null; data: T | null });
+}
+
+export declare class System {
+  static sleep(options: { milliseconds: number }): Promise<void>;
+}
+
+export declare const Key: {
+  readonly Alt: "Alt";
+  readonly Backspace: "Backspace";
+  readonly CapsLock: "CapsLock";
+  readonly Control: "Control";
+  readonly Delete: "Delete";
+  readonly DownArrow: "DownArrow";
+  readonly End: "End";
+  readonly Escape: "Escape";
+  readonly F1: "F1";
+  readonly F2: "F2";
+  readonly F3: "F3";
+  readonly F4: "F4";
+  readonly F5: "F5";
+  readonly F6: "F6";
+  readonly F7: "F7";
+  readonly F8: "F8";
+  readonly F9: "F9";
+  readonly F10: "F10";
+  readonly F11: "F11";
+  readonly F12: "F12";
+  readonly F13: "F13";
+  readonly F14: "F14";
+  readonly F15: "F15";
+  readonly F16: "F16";
+  readonly F17: "F17";
+  readonly F18: "F18";
+  readonly F19: "F19";
+  readonly F20: "F20";
+  readonly Home: "Home";
+  readonly LeftArrow: "LeftArrow";
+  readonly Meta: "Meta";
+  readonly PageDown: "PageDown";
+  readonly PageUp: "PageUp";
+  readonly Return: "Return";
+  readonly RightArrow: "RightArrow";
+  readonly Shift: "Shift";
+  readonly Space: "Space";
+  readonly Tab: "Tab";
+  readonly UpArrow: "UpArrow";
+  readonly LeftBracket: "LeftBracket";
+  readonly RightBracket: "RightBracket";
+  readonly Num1: "Num1";
+  readonly Num2: "Num2";
+  readonly Num3: "Num3";
+  readonly Num4: "Num4";
+  readonly Num5: "Num5";
+  readonly Num6: "Num6";
+  readonly Num7: "Num7";
+  readonly Num8: "Num8";
+  readonly Num9: "Num9";
+  readonly Num0: "Num0";
+  readonly Minus: "Minus";
+  readonly Equal: "Equal";
+  readonly Q: "Q";
+  readonly W: "W";
+  readonly E: "E";
+  readonly R: "R";
+  readonly T: "T";
+  readonly Y: "Y";
+  readonly U: "U";
+  readonly I: "I";
+  readonly O: "O";
+  readonly P: "P";
+  readonly A: "A";
+  readonly S: "S";
+  readonly D: "D";
+  readonly F: "F";
+  readonly G: "G";
+  readonly H: "H";
+  readonly J: "J";
+  readonly K: "K";
+  readonly L: "L";
+  readonly Z: "Z";
+  readonly X: "X";
+  readonly C: "C";
+  readonly V: "V";
+  readonly B: "B";
+  readonly N: "N";
+  readonly M: "M";
+  readonly SemiColon: "SemiColon";
+  readonly Quote: "Quote";
+  readonly BackSlash: "BackSlash";
+  readonly Comma: "Comma";
+  readonly Dot: "Dot";
+  readonly Slash: "Slash";
+  readonly KpMinus: "KpMinus";
+  readonly KpPlus: "KpPlus";
+  readonly KpMultiply: "KpMultiply";
+  readonly KpDivide: "KpDivide";
+  readonly Kp0: "Kp0";
+  readonly Kp1: "Kp1";
+  readonly Kp2: "Kp2";
+  readonly Kp3: "Kp3";
+  readonly Kp4: "Kp4";
+  readonly Kp5: "Kp5";
+  readonly Kp6: "Kp6";
+  readonly Kp7: "Kp7";
+  readonly Kp8: "Kp8";
+  readonly Kp9: "Kp9";
+  readonly KpDelete: "KpDelete";
+  readonly BackQuote: "BackQuote";
+  readonly Insert: "Insert";
+  readonly PrintScreen: "PrintScreen";
+  readonly ScrollLock: "ScrollLock";
+  readonly Pause: "Pause";
+  readonly NumLock: "NumLock";
+  readonly AltRight: "AltRight";
+  readonly ControlRight: "ControlRight";
+  readonly MetaRight: "MetaRight";
+  readonly ShiftRight: "ShiftRight";
+};
+
+export type Key = (typeof Key)[keyof typeof Key];
+
+export type Button = "left" | "right" | "middle";
+
+export interface Region {
+  startPoint: PointLike;
+  endPoint: PointLike;
+  policy?: "reject" | "clamp";
+}
+
+export interface InputHumanize {
+  clickHoldMinMs?: number;
+  clickHoldMaxMs?: number;
+  keyDelayMinMs?: number;
+  keyDelayMaxMs?: number;
+  clickOffset?: number;
+  seed?: number | null;
+}
+
+export declare class ScreenClient {
+  constructor(options?: { port?: number });
+  close(): void;
+
+  findImage(options: {
+    subpath: string;
+    startPoint: PointLike;
+    endPoint: PointLike;
+    threshold: number;
+  }): Promise<Response<WeightPoint>>;
+  findImages(options: {
+    subpath: string;
+    startPoint: PointLike;
+    endPoint: PointLike;
+    threshold: number;
+  }): Promise<Response<WeightPoint[]>>;
+  findRelativeColors(options: {
+    vertexHex: string;
+    coloredPoints: ColoredPoint[];
+    startPoint: PointLike;
+    endPoint: PointLike;
+    rgbOffset: RgbOffsetLike;
+  }): Promise<Response<ColoredPoint>>;
+  findColors(options: {
+    hexColors: string[];
+    startPoint: PointLike;
+    endPoint: PointLike;
+    rgbOffset: RgbOffsetLike;
+  }): Promise<Response<ColoredPoint[]>>;
+  recognizeText(options: { startPoint: PointLike; endPoint: PointLike }): Promise<Response>;
+  saveFrame(options: { path: string }): Promise<Response>;
+
+  getMouseLocation(): Promise<Response<Point>>;
+  getMouseButtons(): Promise<Response<Button[]>>;
+  getPressedKeys(): Promise<Response<string[]>>;
+  isKeyPressed(options: { key: Key | string }): Promise<Response<boolean>>;
+  getLockKeys(): Promise<Response>;
+
+  clickLeft(): Promise<Response>;
+  clickRight(): Promise<Response>;
+  clickMiddle(): Promise<Response>;
+  doubleClick(options?: { button?: Button; intervalMs?: number }): Promise<Response>;
+  clickAt(options: {
+    absolutePoint: PointLike;
+    button?: Button;
+    count?: number;
+    intervalMs?: number;
+  }): Promise<Response>;
+  drag(options: {
+    fromPoint: PointLike;
+    toPoint: PointLike;
+    button?: Button;
+    durationMs?: number;
+  }): Promise<Response>;
+  pressLeft(): Promise<Response>;
+  pressRight(): Promise<Response>;
+  pressMiddle(): Promise<Response>;
+  releaseLeft(): Promise<Response>;
+  releaseRight(): Promise<Response>;
+  releaseMiddle(): Promise<Response>;
+  moveAbsolute(options: { absolutePoint: PointLike }): Promise<Response>;
+  moveTo(options: {
+    absolutePoint: PointLike;
+    durationMs: number;
+    curve?: string;
+    jitter?: number;
+    overshoot?: number;
+  }): Promise<Response>;
+  moveRelative(options: { offset: PointLike }): Promise<Response>;
+  scrollVertical(options: { length: number }): Promise<Response>;
+  scrollHorizontal(options: { length: number }): Promise<Response>;
+
+  pressKey(options: { key: Key | string }): Promise<Response>;
+  releaseKey(options: { key: Key | string }): Promise<Response>;
+  clickKey(options: { key: Key | string }): Promise<Response>;
+  hotkey(options: { keys: string; holdMs?: number; intervalMs?: number }): Promise<Response>;
+  inputCopy(): Promise<Response>;
+  inputPaste(): Promise<Response>;
+  inputCut(): Promise<Response>;
+  inputSelectAll(): Promise<Response>;
+  inputText(options: { text: string; strategy?: "type" | "paste" }): Promise<Response>;
+
+  getClipboardText(): Promise<Response<string>>;
+  setClipboardText(options: { text: string }): Promise<Response>;
+  getClipboardImage(): Promise<Response>;
+
+  setInputMode(options: { mode: string }): Promise<Response>;
+  getInputMode(): Promise<Response<string>>;
+  setInputDryRun(options: { enabled: boolean; path?: string | null }): Promise<Response>;
+  getInputLog(): Promise<Response>;
+  clearInputLog(): Promise<Response>;
+  setInputRegion(options: Region): Promise<Response>;
+  clearInputRegion(): Promise<Response>;
+  getInputRegion(): Promise<Response>;
+  setInputHumanize(options?: InputHumanize): Promise<Response>;
+  clearInputHumanize(): Promise<Response>;
+  getInputHumanize(): Promise<Response>;
+}
@@ -0,0 +1,8 @@
+export { ScreenClient } from "./screen_client.js";
+export { Point } from "./point.js";
+export { ColoredPoint } from "./colored_point.js";
+export { Response } from "./response.js";
+export { RgbOffset } from "./rgb_offset.js";
+export { System } from "./system.js";
+export { Key } from "./key.js";
+export { WeightPoint } from "./weight_point.js";
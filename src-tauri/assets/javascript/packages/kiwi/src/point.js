@@ -0,0 +1,17 @@
+export class Point {
+  constructor({ x, y }) {
+    this.x = x;
+    this.y = y;
+  }
+
+  toJSON() {
+    return { x: this.x, y: this.y };
+  }
+
+  static fromObject(object) {
+    if (object == null) {
+      return null;
+    }
+    return new Point({ x: object.x, y: object.y });
+  }
+}
@@ -0,0 +1,7 @@
+export class Response {
+  constructor({ status, message, data }) {
+    this.status = status;
+    this.message = message;
+    this.data = data;
+  }
+}
@@ -0,0 +1,18 @@
+export class RgbOffset {
+  constructor({ r, g, b }) {
+    this.r = r;
+    this.g = g;
+    this.b = b;
+  }
+
+  toJSON() {
+    return { r: this.r, g: this.g, b: this.b };
+  }
+
+  static fromObject(object) {
+    if (object == null) {
+      return null;
+    }
+    return new RgbOffset({ r: object.r, g: object.g, b: object.b });
+  }
+}
@@ -0,0 +1,416 @@
+import { parseArgs } from "node:util";
+import { ColoredPoint } from "./colored_point.js";
+import { Point } from "./point.js";
+import { Response } from "./response.js";
+import { WeightPoint } from "./weight_point.js";
+
+const DEFAULT_PORT = 9927;
+
+// 空闲超过该时长后关闭连接，脚本执行完毕后进程才能正常退出
+const IDLE_CLOSE_MILLIS = 1000;
+
+export class ScreenClient {
+  constructor({ port } = {}) {
+    this.port = port ?? parsePort();
+    this.ws = null;
+    this.connecting = null;
+    this.queue = Promise.resolve();
+    this.pending = 0;
+    this.idleTimer = null;
+  }
+
+  async _connect() {
+    if (this.ws !== null && this.ws.readyState === WebSocket.OPEN) {
+      return this.ws;
+    }
+    if (this.connecting === null) {
+      this.connecting = new Promise((resolve, reject) => {
+        const ws = new WebSocket(`ws://127.0.0.1:${this.port}/${runQuery()}`);
+        ws.addEventListener("open", () => resolve(ws), { once: true });
+        ws.addEventListener(
+          "error",
+          () => reject(new Error(`Unable to connect to ws://127.0.0.1:${this.port}/`)),
+          { once: true },
+        );
+      }).finally(() => {
+        this.connecting = null;
+      });
+    }
+    this.ws = await this.connecting;
+    return this.ws;
+  }
+
+  close() {
+    clearTimeout(this.idleTimer);
+    this.idleTimer = null;
+    if (this.ws === null) {
+      return;
+    }
+    try {
+      this.ws.close();
+    } catch (error) {
+      console.error("Error on close:", error);
+    }
+    this.ws = null;
+  }
+
+  // 服务端按顺序一问一答，请求需要排队发送
+  _sendAndReceive(method, args) {
+    clearTimeout(this.idleTimer);
+    this.pending += 1;
+    const request = this.queue.then(() => this._request(method, args));
+    this.queue = request
+      .catch(() => {})
+      .finally(() => {
+        this.pending -= 1;
+        if (this.pending === 0) {
+          this.idleTimer = setTimeout(() => this.close(), IDLE_CLOSE_MILLIS);
+        }
+      });
+    return request;
+  }
+
+  async _request(method, args) {
+    const data = JSON.stringify({ method, args });
+    try {
+      return await this._exchange(await this._connect(), data);
+    } catch {
+      this.close();
+      return await this._exchange(await this._connect(), data);
+    }
+  }
+
+  _exchange(ws, data) {
+    return new Promise((resolve, reject) => {
+      const onMessage = (event) => {
+        cleanup();
+        resolve(this._parseResponse(String(event.data)));
+      };
+      const onClose = () => {
+        cleanup();
+        reject(new Error("Connection closed."));
+      };
+      const cleanup = () => {
+        ws.removeEventListener("message", onMessage);
+        ws.removeEventListener("close", onClose);
+      };
+      ws.addEventListener("message", onMessage);
+      ws.addEventListener("close", onClose);
+      ws.send(data);
+    });
+  }
+
+  _parseResponse(jsonStr) {
+    if (!jsonStr.trim()) {
+      return null;
+    }
+    let jsonData;
+    try {
+      jsonData = JSON.parse(jsonStr);
+    } catch (error) {
+      console.error(`JSON decode error: ${error}`);
+      return null;
+    }
+    if (!("status" in jsonData && "message" in jsonData && "data" in jsonData)) {
+      console.error("Response missing required keys");
+      return null;
+    }
+    return new Response({
+      status: jsonData.status,
+      message: jsonData.message ?? null,
+      data: jsonData.data ?? null,
+    });
+  }
+
+  async findImage({ subpath, startPoint, endPoint, threshold }) {
+    const args = {
+      subpath,
+      start_point: startPoint,
+      end_point: endPoint,
+      threshold,
+    };
+    const response = await this._sendAndReceive("find_image", args);
+    response.data = WeightPoint.fromObject(response.data);
+    return response;
+  }
+
+  async findImages({ subpath, startPoint, endPoint, threshold }) {
+    const args = {
+      subpath,
+      start_point: startPoint,
+      end_point: endPoint,
+      threshold,
+    };
+    const response = await this._sendAndReceive("find_images", args);
+    response.data = WeightPoint.fromArray(response.data);
+    return response;
+  }
+
+  async findRelativeColors({ vertexHex, coloredPoints, startPoint, endPoint, rgbOffset }) {
+    const args = {
+      vertex_hex: vertexHex,
+      colored_points: coloredPoints,
+      start_point: startPoint,
+      end_point: endPoint,
+      rgb_offset: rgbOffset,
+    };
+    const response = await this._sendAndReceive("find_relative_colors", args);
+    response.data = ColoredPoint.fromObject(response.data);
+    return response;
+  }
+
+  async findColors({ hexColors, startPoint, endPoint, rgbOffset }) {
+    const args = {
+      hex_colors: hexColors,
+      start_point: startPoint,
+      end_point: endPoint,
+      rgb_offset: rgbOffset,
+    };
+    const response = await this._sendAndReceive("find_colors", args);
+    response.data = ColoredPoint.fromArray(response.data);
+    return response;
+  }
+
+  recognizeText({ startPoint, endPoint }) {
+    const args = { start_point: startPoint, end_point: endPoint };
+    return this._sendAndReceive("recognize_text", args);
+  }
+
+  saveFrame({ path }) {
+    return this._sendAndReceive("save_frame", { path });
+  }
+
+  async getMouseLocation() {
+    const response = await this._sendAndReceive("get_mouse_location", {});
+    response.data = Point.fromObject(response.data);
+    return response;
+  }
+
+  getMouseButtons() {
+    return this._sendAndReceive("get_mouse_buttons", {});
+  }
+
+  getPressedKeys() {
+    return this._sendAndReceive("get_pressed_keys", {});
+  }
+
+  isKeyPressed({ key }) {
+    return this._sendAndReceive("is_key_pressed", { key });
+  }
+
+  getLockKeys() {
+    return this._sendAndReceive("get_lock_keys", {});
+  }
+
+  clickLeft() {
+    return this._sendAndReceive("click_left", {});
+  }
+
+  clickRight() {
+    return this._sendAndReceive("click_right", {});
+  }
+
+  clickMiddle() {
+    return this._sendAndReceive("click_middle", {});
+  }
+
+  doubleClick({ button = "left", intervalMs = 50 } = {}) {
+    const args = { button, interval_ms: intervalMs };
+    return this._sendAndReceive("double_click", args);
+  }
+
+  clickAt({ absolutePoint, button = "left", count = 1, intervalMs = 50 }) {
+    const args = {
+      absolute_point: absolutePoint,
+      button,
+      count,
+      interval_ms: intervalMs,
+    };
+    return this._sendAndReceive("click_at", args);
+  }
+
+  drag({ fromPoint, toPoint, button = "left", durationMs = 300 }) {
+    const args = {
+      from: fromPoint,
+      to: toPoint,
+      button,
+      duration_ms: durationMs,
+    };
+    return this._sendAndReceive("drag", args);
+  }
+
+  pressLeft() {
+    return this._sendAndReceive("press_left", {});
+  }
+
+  pressRight() {
+    return this._sendAndReceive("press_right", {});
+  }
+
+  pressMiddle() {
+    return this._sendAndReceive("press_middle", {});
+  }
+
+  releaseLeft() {
+    return this._sendAndReceive("release_left", {});
+  }
+
+  releaseRight() {
+    return this._sendAndReceive("release_right", {});
+  }
+
+  releaseMiddle() {
+    return this._sendAndReceive("release_middle", {});
+  }
+
+  moveAbsolute({ absolutePoint }) {
+    return this._sendAndReceive("move_absolute", { absolute_point: absolutePoint });
+  }
+
+  moveTo({ absolutePoint, durationMs, curve = "linear", jitter = 0.0, overshoot = 0.0 }) {
+    const args = {
+      absolute_point: absolutePoint,
+      duration_ms: durationMs,
+      curve,
+      jitter,
+      overshoot,
+    };
+    return this._sendAndReceive("move_to", args);
+  }
+
+  moveRelative({ offset }) {
+    return this._sendAndReceive("move_relative", { offset });
+  }
+
+  scrollVertical({ length }) {
+    return this._sendAndReceive("scroll_vertical", { length });
+  }
+
+  scrollHorizontal({ length }) {
+    return this._sendAndReceive("scroll_horizontal", { length });
+  }
+
+  pressKey({ key }) {
+    return this._sendAndReceive("press_key", { key });
+  }
+
+  releaseKey({ key }) {
+    return this._sendAndReceive("release_key", { key });
+  }
+
+  clickKey({ key }) {
+    return this._sendAndReceive("click_key", { key });
+  }
+
+  hotkey({ keys, holdMs = 0, intervalMs = 50 }) {
+    const args = { keys, hold_ms: holdMs, interval_ms: intervalMs };
+    return this._sendAndReceive("hotkey", args);
+  }
+
+  inputCopy() {
+    return this.hotkey({ keys: process.platform === "darwin" ? "meta+c" : "ctrl+c" });
+  }
+
+  inputPaste() {
+    return this.hotkey({ keys: process.platform === "darwin" ? "meta+v" : "ctrl+v" });
+  }
+
+  inputCut() {
+    return this.hotkey({ keys: process.platform === "darwin" ? "meta+x" : "ctrl+x" });
+  }
+
+  inputSelectAll() {
+    return this.hotkey({ keys: process.platform === "darwin" ? "meta+a" : "ctrl+a" });
+  }
+
+  inputText({ text, strategy = "type" }) {
+    return this._sendAndReceive("input_text", { text, strategy });
+  }
+
+  getClipboardText() {
+    return this._sendAndReceive("get_clipboard_text", {});
+  }
+
+  setClipboardText({ text }) {
+    return this._sendAndReceive("set_clipboard_text", { text });
+  }
+
+  getClipboardImage() {
+    return this._sendAndReceive("get_clipboard_image", {});
+  }
+
+  setInputMode({ mode }) {
+    return this._sendAndReceive("set_input_mode", { mode });
+  }
+
+  getInputMode() {
+    return this._sendAndReceive("get_input_mode", {});
+  }
+
+  setInputDryRun({ enabled, path = null }) {
+    return this._sendAndReceive("set_input_dry_run", { enabled, path });
+  }
+
+  getInputLog() {
+    return this._sendAndReceive("get_input_log", {});
+  }
+
+  clearInputLog() {
+    return this._sendAndReceive("clear_input_log", {});
+  }
+
+  setInputRegion({ startPoint, endPoint, policy = "reject" }) {
+    const args = { start_point: startPoint, end_point: endPoint, policy };
+    return this._sendAndReceive("set_input_region", args);
+  }
+
+  clearInputRegion() {
+    return this._sendAndReceive("clear_input_region", {});
+  }
+
+  getInputRegion() {
+    return this._sendAndReceive("get_input_region", {});
+  }
+
+  setInputHumanize({
+    clickHoldMinMs = 40,
+    clickHoldMaxMs = 120,
+    keyDelayMinMs = 30,
+    keyDelayMaxMs = 150,
+    clickOffset = 2.0,
+    seed = null,
+  } = {}) {
+    const args = {
+      click_hold_min_ms: clickHoldMinMs,
+      click_hold_max_ms: clickHoldMaxMs,
+      key_delay_min_ms: keyDelayMinMs,
+      key_delay_max_ms: keyDelayMaxMs,
+      click_offset: clickOffset,
+      seed,
+    };
+    return this._sendAndReceive("set_input_humanize", args);
+  }
+
+  clearInputHumanize() {
+    return this._sendAndReceive("clear_input_humanize", {});
+  }
+
+  getInputHumanize() {
+    return this._sendAndReceive("get_input_humanize", {});
+  }
+}
+
+// 带上运行编号，Kiwi 按运行记录调用，用于检测脚本是否没有响应
+function runQuery() {
+  const runId = process.env.KIWI_RUN_ID;
+  return runId ? `?run=${runId}` : "";
+}
+
+function parsePort() {
+  const { values } = parseArgs({
+    options: { port: { type: "string" } },
+    strict: false,
+  });
+  const port = Number.parseInt(values.port ?? "", 10);
+  return Number.isNaN(port) ? DEFAULT_PORT : port;
+}
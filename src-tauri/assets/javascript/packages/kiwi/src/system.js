@@ -0,0 +1,8 @@
+export class System {
+  static sleep({ milliseconds }) {
+    if (milliseconds <= 0) {
+      return Promise.resolve();
+    }
+    return new Promise((resolve) => setTimeout(resolve, milliseconds));
+  }
+}
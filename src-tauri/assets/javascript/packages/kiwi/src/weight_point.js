@@ -0,0 +1,29 @@
+import { Point } from "./point.js";
+
+export class WeightPoint {
+  constructor({ point, weight }) {
+    this.point = point;
+    this.weight = weight;
+  }
+
+  toJSON() {
+    return { point: this.point, weight: this.weight };
+  }
+
+  static fromObject(object) {
+    if (object == null) {
+      return null;
+    }
+    return new WeightPoint({
+      point: Point.fromObject(object.point),
+      weight: object.weight,
+    });
+  }
+
+  static fromArray(array) {
+    if (array == null) {
+      return [];
+    }
+    return array.filter((object) => object != null).map(WeightPoint.fromObject);
+  }
+}
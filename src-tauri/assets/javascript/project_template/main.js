@@ -0,0 +1,18 @@
+import {
+  ColoredPoint,
+  Key,
+  Point,
+  Response,
+  RgbOffset,
+  ScreenClient,
+  System,
+  WeightPoint,
+} from "kiwi";
+
+const client = new ScreenClient();
+
+while (true) {
+  const data = (await client.getMouseLocation()).data;
+  console.log("location:", data, data.x, data.y);
+  await System.sleep({ milliseconds: 1000 });
+}
@@ -0,0 +1,10 @@
+-- 脚本在 Kiwi 中运行，client 的方法与 websocket 客户端同名，参数写在表中，
+-- 返回值为结果数据，出错时抛出错误，可以用 pcall 捕获
+local kiwi = require("kiwi")
+local client, System, Key = kiwi.client, kiwi.System, kiwi.Key
+
+while true do
+  local location = client.get_mouse_location()
+  print("location:", location.x, location.y)
+  System.sleep({ milliseconds = 1000 })
+end
@@ -109,6 +109,10 @@ class Key(Enum):
     ScrollLock = "ScrollLock"
     Pause = "Pause"
     NumLock = "NumLock"
+    AltRight = "AltRight"
+    ControlRight = "ControlRight"
+    MetaRight = "MetaRight"
+    ShiftRight = "ShiftRight"
 
     # Function
     # IntlBackslash
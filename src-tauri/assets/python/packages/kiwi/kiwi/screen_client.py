@@ -5,13 +5,13 @@ from typing import Any
 from typing import Optional, cast, Union
 import atexit
 import json
+import os
 import sys
 from .point import Point
 from .colored_point import ColoredPoint
 from .response import Response
 from .rgb_offset import RgbOffset
 from .key import Key
-from .system import System
 from .weight_point import WeightPoint
 
 
@@ -28,7 +28,10 @@ class ScreenClient:
         parser = argparse.ArgumentParser()
         parser.add_argument("--port", type=int, default=9927)
         args, _ = parser.parse_known_args()
-        self.ws = create_connection(f"ws://127.0.0.1:{args.port}/")
+        # 带上运行编号，Kiwi 按运行记录调用，用于检测脚本是否没有响应
+        run_id = os.environ.get("KIWI_RUN_ID")
+        query = f"?run={run_id}" if run_id else ""
+        self.ws = create_connection(f"ws://127.0.0.1:{args.port}/{query}")
 
     def _close(self):
         try:
@@ -166,121 +169,251 @@ class ScreenClient:
         response.data = Point.from_namespace(response.data)
         return response
 
+    def get_mouse_buttons(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_mouse_buttons", args)
+
+    def get_pressed_keys(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_pressed_keys", args)
+
+    def is_key_pressed(self, *, key: str) -> Optional[Response]:
+        args = {"key": key}
+        return self._send_and_receive("is_key_pressed", args)
+
+    def get_lock_keys(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_lock_keys", args)
+
     def click_left(self):
         args = {}
-        self._send_and_receive("click_left", args)
+        return self._send_and_receive("click_left", args)
 
     def click_right(self):
         args = {}
-        self._send_and_receive("click_right", args)
+        return self._send_and_receive("click_right", args)
+
+    def click_middle(self):
+        args = {}
+        return self._send_and_receive("click_middle", args)
+
+    def double_click(self, *, button: str = "left", interval_ms: int = 50):
+        args = {"button": button, "interval_ms": interval_ms}
+        return self._send_and_receive("double_click", args)
+
+    def click_at(
+        self,
+        *,
+        absolute_point: Point,
+        button: str = "left",
+        count: int = 1,
+        interval_ms: int = 50,
+    ):
+        args = {
+            "absolute_point": absolute_point.to_dict(),
+            "button": button,
+            "count": count,
+            "interval_ms": interval_ms,
+        }
+        return self._send_and_receive("click_at", args)
+
+    def drag(
+        self,
+        *,
+        from_point: Point,
+        to_point: Point,
+        button: str = "left",
+        duration_ms: int = 300,
+    ):
+        args = {
+            "from": from_point.to_dict(),
+            "to": to_point.to_dict(),
+            "button": button,
+            "duration_ms": duration_ms,
+        }
+        return self._send_and_receive("drag", args)
 
     def press_left(self):
         args = {}
-        self._send_and_receive("press_left", args)
+        return self._send_and_receive("press_left", args)
 
     def press_right(self):
         args = {}
-        self._send_and_receive("press_right", args)
+        return self._send_and_receive("press_right", args)
+
+    def press_middle(self):
+        args = {}
+        return self._send_and_receive("press_middle", args)
 
     def release_left(self):
         args = {}
-        self._send_and_receive("release_left", args)
+        return self._send_and_receive("release_left", args)
 
     def release_right(self):
         args = {}
-        self._send_and_receive("release_right", args)
+        return self._send_and_receive("release_right", args)
+
+    def release_middle(self):
+        args = {}
+        return self._send_and_receive("release_middle", args)
 
     def move_absolute(self, *, absolute_point: Point):
         args = {"absolute_point": absolute_point.to_dict()}
-        self._send_and_receive("move_absolute", args)
+        return self._send_and_receive("move_absolute", args)
+
+    def move_to(
+        self,
+        *,
+        absolute_point: Point,
+        duration_ms: int,
+        curve: str = "linear",
+        jitter: float = 0.0,
+        overshoot: float = 0.0,
+    ):
+        args = {
+            "absolute_point": absolute_point.to_dict(),
+            "duration_ms": duration_ms,
+            "curve": curve,
+            "jitter": jitter,
+            "overshoot": overshoot,
+        }
+        return self._send_and_receive("move_to", args)
 
     def move_relative(self, *, offset: Point):
         args = {"offset": offset.to_dict()}
-        self._send_and_receive("move_relative", args)
+        return self._send_and_receive("move_relative", args)
 
     def scroll_vertical(self, *, length: int):
         args = {"length": length}
-        self._send_and_receive("scroll_vertical", args)
+        return self._send_and_receive("scroll_vertical", args)
 
     def scroll_horizontal(self, *, length: int):
         args = {"length": length}
-        self._send_and_receive("scroll_horizontal", args)
+        return self._send_and_receive("scroll_horizontal", args)
 
     def press_key(self, *, key: Key):
         key_val = key.value if isinstance(key, Key) else key
         args = {"key": key_val}
-        self._send_and_receive("press_key", args)
+        return self._send_and_receive("press_key", args)
 
     def release_key(self, *, key: Key):
         key_val = key.value if isinstance(key, Key) else key
         args = {"key": key_val}
-        self._send_and_receive("release_key", args)
+        return self._send_and_receive("release_key", args)
 
     def click_key(self, *, key: Key):
         key_val = key.value if isinstance(key, Key) else key
         args = {"key": key_val}
-        self._send_and_receive("click_key", args)
+        return self._send_and_receive("click_key", args)
+
+    def hotkey(self, *, keys: str, hold_ms: int = 0, interval_ms: int = 50):
+        args = {"keys": keys, "hold_ms": hold_ms, "interval_ms": interval_ms}
+        return self._send_and_receive("hotkey", args)
 
     def input_copy(self):
-        system = System()
         if sys.platform == "darwin":
-            self.press_key(key=Key.Meta)
-            system.sleep(milliseconds=20)
-            self.click_key(key="c")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Meta)
+            return self.hotkey(keys="meta+c")
         else:
-            self.press_key(key=Key.Control)
-            system.sleep(milliseconds=20)
-            self.click_key(key="c")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Control)
+            return self.hotkey(keys="ctrl+c")
 
     def input_paste(self):
-        system = System()
         if sys.platform == "darwin":
-            self.press_key(key=Key.Meta)
-            system.sleep(milliseconds=20)
-            self.click_key(key="v")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Meta)
+            return self.hotkey(keys="meta+v")
         else:
-            self.press_key(key=Key.Control)
-            system.sleep(milliseconds=20)
-            self.click_key(key="v")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Control)
+            return self.hotkey(keys="ctrl+v")
 
     def input_cut(self):
-        system = System()
         if sys.platform == "darwin":
-            self.press_key(key=Key.Meta)
-            system.sleep(milliseconds=20)
-            self.click_key(key="x")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Meta)
+            return self.hotkey(keys="meta+x")
         else:
-            self.press_key(key=Key.Control)
-            system.sleep(milliseconds=20)
-            self.click_key(key="x")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Control)
+            return self.hotkey(keys="ctrl+x")
 
     def input_select_all(self):
-        system = System()
         if sys.platform == "darwin":
-            self.press_key(key=Key.Meta)
-            system.sleep(milliseconds=20)
-            self.click_key(key="a")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Meta)
+            return self.hotkey(keys="meta+a")
         else:
-            self.press_key(key=Key.Control)
-            system.sleep(milliseconds=20)
-            self.click_key(key="a")
-            system.sleep(milliseconds=20)
-            self.release_key(key=Key.Control)
+            return self.hotkey(keys="ctrl+a")
+
+    def input_text(self, *, text: str, strategy: str = "type"):
+        args = {"text": text, "strategy": strategy}
+        return self._send_and_receive("input_text", args)
+
+    def get_clipboard_text(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_clipboard_text", args)
 
-    def input_text(self, *, text: str):
+    def set_clipboard_text(self, *, text: str) -> Optional[Response]:
         args = {"text": text}
-        self._send_and_receive("input_text", args)
+        return self._send_and_receive("set_clipboard_text", args)
+
+    def get_clipboard_image(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_clipboard_image", args)
+
+    def set_input_mode(self, *, mode: str) -> Optional[Response]:
+        args = {"mode": mode}
+        return self._send_and_receive("set_input_mode", args)
+
+    def get_input_mode(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_input_mode", args)
+
+    def set_input_dry_run(
+        self, *, enabled: bool, path: Optional[str] = None
+    ) -> Optional[Response]:
+        args = {"enabled": enabled, "path": path}
+        return self._send_and_receive("set_input_dry_run", args)
+
+    def get_input_log(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_input_log", args)
+
+    def clear_input_log(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("clear_input_log", args)
+
+    def set_input_region(
+        self, *, start_point: Point, end_point: Point, policy: str = "reject"
+    ) -> Optional[Response]:
+        args = {
+            "start_point": start_point.to_dict(),
+            "end_point": end_point.to_dict(),
+            "policy": policy,
+        }
+        return self._send_and_receive("set_input_region", args)
+
+    def clear_input_region(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("clear_input_region", args)
+
+    def get_input_region(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_input_region", args)
+
+    def set_input_humanize(
+        self,
+        *,
+        click_hold_min_ms: int = 40,
+        click_hold_max_ms: int = 120,
+        key_delay_min_ms: int = 30,
+        key_delay_max_ms: int = 150,
+        click_offset: float = 2.0,
+        seed: Optional[int] = None,
+    ) -> Optional[Response]:
+        args = {
+            "click_hold_min_ms": click_hold_min_ms,
+            "click_hold_max_ms": click_hold_max_ms,
+            "key_delay_min_ms": key_delay_min_ms,
+            "key_delay_max_ms": key_delay_max_ms,
+            "click_offset": click_offset,
+            "seed": seed,
+        }
+        return self._send_and_receive("set_input_humanize", args)
+
+    def clear_input_humanize(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("clear_input_humanize", args)
+
+    def get_input_humanize(self) -> Optional[Response]:
+        args = {}
+        return self._send_and_receive("get_input_humanize", args)
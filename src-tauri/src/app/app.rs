@@ -2,9 +2,12 @@
 use super::Config;
 use crate::{
     capture::{Engine as CaptureEngine, Frame},
+    failsafe::Engine as FailsafeEngine,
     input::Engine as InputEngine,
     project::Project,
-    record::Engine as RecordEngine,
+    record::{Engine as RecordEngine, Replayer},
+    run::Runs,
+    schedule::Scheduler,
 };
 use anyhow::{Result, anyhow};
 use std::path::PathBuf;
@@ -25,10 +28,26 @@ impl App {
         RECORDER.get_or_init(|| Arc::new(RecordEngine::default()))
     }
 
+    fn init_replayer() -> &'static Arc<Replayer> {
+        REPLAYER.get_or_init(|| Arc::new(Replayer::default()))
+    }
+
     fn init_input() -> &'static Arc<InputEngine> {
         INPUT.get_or_init(|| Arc::new(InputEngine::default()))
     }
 
+    fn init_failsafe() -> &'static Arc<FailsafeEngine> {
+        FAILSAFE.get_or_init(|| Arc::new(FailsafeEngine::default()))
+    }
+
+    fn init_runs() -> &'static Arc<Runs> {
+        RUNS.get_or_init(|| Arc::new(Runs::default()))
+    }
+
+    fn init_scheduler() -> &'static Arc<Scheduler> {
+        SCHEDULER.get_or_init(|| Arc::new(Scheduler::default()))
+    }
+
     pub fn get() -> Arc<Self> {
         APP.get_or_init(|| Arc::new(App::default())).clone()
     }
@@ -53,10 +72,26 @@ impl App {
         Self::init_recorder().clone()
     }
 
+    pub fn replayer() -> Arc<Replayer> {
+        Self::init_replayer().clone()
+    }
+
     pub fn input() -> Arc<InputEngine> {
         Self::init_input().clone()
     }
 
+    pub fn failsafe() -> Arc<FailsafeEngine> {
+        Self::init_failsafe().clone()
+    }
+
+    pub fn runs() -> Arc<Runs> {
+        Self::init_runs().clone()
+    }
+
+    pub fn scheduler() -> Arc<Scheduler> {
+        Self::init_scheduler().clone()
+    }
+
     pub fn project(&self) -> Result<RwLockReadGuard<Option<Project>>> {
         self.project.read().map_err(|e| {
             anyhow!(t!(
@@ -101,10 +136,26 @@ impl App {
         f(&*Self::init_recorder())
     }
 
+    pub fn with_replayer<R>(f: impl FnOnce(&Replayer) -> R) -> R {
+        f(&*Self::init_replayer())
+    }
+
     pub fn with_input<R>(f: impl FnOnce(&InputEngine) -> R) -> R {
         f(&*Self::init_input())
     }
 
+    pub fn with_failsafe<R>(f: impl FnOnce(&FailsafeEngine) -> R) -> R {
+        f(&*Self::init_failsafe())
+    }
+
+    pub fn with_runs<R>(f: impl FnOnce(&Runs) -> R) -> R {
+        f(&*Self::init_runs())
+    }
+
+    pub fn with_scheduler<R>(f: impl FnOnce(&Scheduler) -> R) -> R {
+        f(&*Self::init_scheduler())
+    }
+
     pub fn try_with_project<R>(f: impl FnOnce(&Project) -> R) -> Result<R> {
         let app = App::get();
         let guard = app.project.read().unwrap();
@@ -195,6 +246,10 @@ impl Default for App {
 static APP: OnceLock<Arc<App>> = OnceLock::new();
 static CAPTURER: OnceLock<Arc<CaptureEngine>> = OnceLock::new();
 static RECORDER: OnceLock<Arc<RecordEngine>> = OnceLock::new();
+static REPLAYER: OnceLock<Arc<Replayer>> = OnceLock::new();
 static INPUT: OnceLock<Arc<InputEngine>> = OnceLock::new();
+static FAILSAFE: OnceLock<Arc<FailsafeEngine>> = OnceLock::new();
+static RUNS: OnceLock<Arc<Runs>> = OnceLock::new();
+static SCHEDULER: OnceLock<Arc<Scheduler>> = OnceLock::new();
 static RESOURCE_DIR: OnceLock<PathBuf> = OnceLock::new();
 static APP_HANDLE: OnceLock<Arc<AppHandle>> = OnceLock::new();
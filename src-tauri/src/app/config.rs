@@ -1,12 +1,19 @@
 // done
 use super::App;
-use anyhow::Result;
+use crate::input::{Chord, parse_hotkey};
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, time::Duration};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub app: ConfigApp,
+    #[serde(default)]
+    pub failsafe: ConfigFailsafe,
+    #[serde(default)]
+    pub recorder: ConfigRecorder,
+    #[serde(default)]
+    pub stop: ConfigStop,
 }
 
 impl Default for Config {
@@ -33,6 +40,105 @@ pub struct ConfigApp {
     pub locale: String,
 }
 
+/// 紧急停止：按下热键或把鼠标移到屏幕角落时，立即停止正在运行的脚本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfigFailsafe {
+    pub enabled: bool,
+    pub hotkey: String,
+    pub corner: bool,
+}
+
+impl ConfigFailsafe {
+    /// 解析紧急停止热键，只支持一个组合键，例如 `ctrl+alt+escape`
+    pub fn chord(&self) -> Result<Chord> {
+        let mut chords = parse_hotkey(&self.hotkey)?;
+        if chords.len() != 1 {
+            return Err(anyhow!(t!(
+                "The failsafe hotkey must be a single key combination.",
+                hotkey = self.hotkey.trim()
+            )));
+        }
+        Ok(chords.remove(0))
+    }
+}
+
+impl Default for ConfigFailsafe {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hotkey: "ctrl+alt+escape".to_string(),
+            corner: true,
+        }
+    }
+}
+
+/// 停止脚本
+///
+/// `grace_millis` 为请求脚本退出后等待的时间（毫秒），超时后强制结束脚本及其子进程，`0` 表示直接强制结束。
+/// 紧急停止不等待。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfigStop {
+    pub grace_millis: u64,
+}
+
+impl Default for ConfigStop {
+    fn default() -> Self {
+        Self { grace_millis: 3000 }
+    }
+}
+
+impl ConfigStop {
+    pub fn grace(&self) -> Duration {
+        Duration::from_millis(self.grace_millis)
+    }
+}
+
+/// 录制热键
+///
+/// `start`/`stop` 为空时使用默认热键（Windows 上为 `ctrl+F9`/`ctrl+F12`，其他平台为 `F9`/`F12`），
+/// 其余热键为空时不启用。`pause` 和 `resume` 相同时按一次暂停、再按一次继续，`marker` 在录制中插入检查点。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfigRecorder {
+    pub start: String,
+    pub stop: String,
+    pub pause: String,
+    pub resume: String,
+    pub marker: String,
+}
+
+impl Default for ConfigRecorder {
+    fn default() -> Self {
+        Self {
+            start: "".to_string(),
+            stop: "".to_string(),
+            pause: "F7".to_string(),
+            resume: "F7".to_string(),
+            marker: "F8".to_string(),
+        }
+    }
+}
+
+impl ConfigRecorder {
+    pub fn get_start(&self) -> String {
+        match self.start.trim() {
+            "" if cfg!(target_os = "windows") => "ctrl+F9".to_string(),
+            "" => "F9".to_string(),
+            start => start.to_string(),
+        }
+    }
+
+    pub fn get_stop(&self) -> String {
+        match self.stop.trim() {
+            "" if cfg!(target_os = "windows") => "ctrl+F12".to_string(),
+            "" => "F12".to_string(),
+            stop => stop.to_string(),
+        }
+    }
+}
+
 pub fn get_config_toml_path() -> PathBuf {
     App::get_resource_dir().join("config.toml")
 }
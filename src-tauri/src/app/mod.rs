@@ -2,5 +2,5 @@ mod app;
 mod config;
 mod log;
 pub use app::*;
-pub use config::Config;
+pub use config::{Config, ConfigFailsafe, ConfigRecorder, ConfigStop};
 pub use log::Log;
@@ -6,7 +6,7 @@ use anyhow::{Result, anyhow};
 use arc_swap::ArcSwapOption;
 use capture::{Config, Engine as CaptureEngine, Frame as CaptureFrame};
 use display_info::DisplayInfo;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager};
 
 #[cfg(target_os = "macos")]
@@ -20,6 +20,15 @@ use windows::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WINDOW_D
 pub struct Engine {
     engine: Arc<CaptureEngine>,
     frame: ArcSwapOption<Frame>,
+    /// 共用后台截图的脚本
+    users: Mutex<Users>,
+}
+
+/// `generation` 在强制停止截图时递增，之前的使用者释放时被忽略
+#[derive(Default)]
+struct Users {
+    count: usize,
+    generation: u64,
 }
 
 impl Engine {
@@ -32,9 +41,45 @@ impl Engine {
     }
 
     pub fn stop(&self) {
+        let mut users = self.users.lock().unwrap();
+        users.count = 0;
+        users.generation += 1;
         self.engine.stop();
     }
 
+    /// 同时运行的脚本共用后台截图，第一个使用者开始截图，返回释放时需要传回的编号
+    pub fn acquire(&self) -> Result<u64> {
+        let mut users = self.users.lock().unwrap();
+        if users.count == 0 {
+            // 上一次截图还没有完全停止
+            if self.engine.is_running() {
+                return Err(anyhow!(t!("Screen capture is still running. Please wait.")));
+            }
+            self.clear_frame();
+            self.engine.start_background()?;
+        }
+        users.count += 1;
+        Ok(users.generation)
+    }
+
+    /// 最后一个使用者释放后停止截图，截图被强制停止之前的使用者释放时不做任何事
+    pub fn release(&self, generation: u64) {
+        let mut users = self.users.lock().unwrap();
+        if users.generation != generation || users.count == 0 {
+            return;
+        }
+        users.count -= 1;
+        if users.count == 0 {
+            self.engine.stop();
+        }
+    }
+
+    /// `generation` 为 [`Engine::acquire`] 返回的编号，截图被强制停止后返回 `false`
+    pub fn is_current(&self, generation: u64) -> bool {
+        let users = self.users.lock().unwrap();
+        users.generation == generation && users.count > 0
+    }
+
     pub fn is_running(&self) -> bool {
         self.engine.is_running()
     }
@@ -189,6 +234,7 @@ impl Default for Engine {
         Self {
             engine,
             frame: ArcSwapOption::empty(),
+            users: Mutex::new(Users::default()),
         }
     }
 }
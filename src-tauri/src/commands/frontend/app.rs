@@ -22,10 +22,13 @@ pub fn save_app_config(app_handle: AppHandle, config: AppConfig) -> CommandResul
     if config.app.websocket_port == 0 {
         return Err("WebSocket port must be greater than 0.".into());
     }
+    config.failsafe.chord()?;
 
     App::with_config_mut(|app_config| {
         app_config.app.websocket_port = config.app.websocket_port;
         app_config.app.locale = config.app.locale.clone();
+        app_config.failsafe = config.failsafe.clone();
+        app_config.recorder = config.recorder.clone();
         app_config.save()
     })?;
 
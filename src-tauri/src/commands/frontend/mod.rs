@@ -6,4 +6,6 @@ use result::*;
 pub mod common;
 pub mod frame;
 pub mod project;
+pub mod record;
+pub mod schedule;
 pub mod utils;
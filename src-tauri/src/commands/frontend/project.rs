@@ -4,19 +4,27 @@ use super::{
 };
 use crate::app::{App, Log};
 use crate::{
-    interpreter::Interpreter,
-    project::{Config, Project, ProjectInfo, VerifyStatusString},
-    types::{Base64Png, Base64PngExt as _, Progress, Size},
+    input::{Press, Token},
+    interpreter::{Interpreter, RunOptions},
+    project::{Config, Project, ProjectInfo, RECORD_EVENTS_SUFFIX, VerifyStatusString},
+    record::{
+        EventWriter, HotkeyFilter, ImageRecorder, RecordAction, RecordEvent, RecordEventKind,
+        RecordHotkeys, RecordInput, RegionFilter, ScriptWriter,
+    },
+    run::{self, RunId, RunInfo, RunLogLine, RunRequest, Watchdog},
+    types::{Base64Png, Base64PngExt as _, Point, Progress, Size},
 };
 use anyhow::Error;
 use fs_extra::dir;
 use std::{
-    fs::File,
-    io::{BufRead as _, BufReader, BufWriter, Write},
+    collections::BTreeMap,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
     thread,
-    time::{Duration, Instant},
+    time::Instant,
 };
 use tauri::{AppHandle, ipc::Response};
 
@@ -97,9 +105,14 @@ pub fn open_project(app: AppHandle, path: String) -> CommandResult<ProjectInfo>
     let project_path = PathBuf::from(path);
     let project = Project::new_from_project_path(project_path)?;
     let project_info: ProjectInfo = ProjectInfo::from(&project);
+    App::input().set_region(project.config.input.region)?;
+    App::input().set_humanize(project.config.input.humanize)?;
+    let schedules = project.config.schedules.clone();
     let app = App::get();
     let mut project_guard = app.project_mut()?;
     *project_guard = Some(project);
+    drop(project_guard);
+    App::with_scheduler(|scheduler| scheduler.load(schedules));
     emit(&app_handle, "backend:update:project", &project_info);
     Ok(project_info)
 }
@@ -169,86 +182,81 @@ pub fn get_image_size(name: String, data_path: String) -> CommandResult<Size> {
     Ok(Size::new(image.width(), image.height()))
 }
 
+/// 运行脚本，返回运行编号，`profile` 选择项目配置中的运行配置
+///
+/// `args`、`cwd` 和 `watchdog` 替换运行配置中的值，`env` 与运行配置中的环境变量合并，同名时覆盖
 #[tauri::command]
-pub fn run_script(app_handle: AppHandle, path: String) {
-    let app_handle = Arc::new(app_handle);
-
-    if let Err(error) = App::try_with_project(|_| {}) {
-        Log::error(error.to_string()).send_to_app_log();
-        return;
-    }
-
-    if App::with_capturer(|capturer| capturer.is_running()) {
-        Log::error(t!("Screen capture is still running. Please wait.")).send_to_app_log();
-        return;
-    }
-
-    if App::with_recorder(|recorder| recorder.is_running()) {
-        Log::error(t!("Recorder is running. Please stop it first.")).send_to_app_log();
-        return;
-    }
-
-    let app_handle_spawned = Arc::clone(&app_handle);
-    let app_handle_wait = Arc::clone(&app_handle);
-    let on_spawned = move |pid| {
-        let msg = t!("The script is now running.", pid = pid);
-        Log::success(msg.to_string()).send_to_app_log();
-        emit(&app_handle_spawned, "run:status", "running");
-    };
-    let on_stdout = move |stdout| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    Log::info(&line).send_to_app_log();
-                }
-            }
-        });
-    };
-    let on_stderr = move |stderr| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    Log::error(&line).send_to_app_log();
-                }
-            }
-        });
-    };
-    let on_exit = move |pid, exit_status| {
-        let msg = t!("The script has completed.", pid = pid, status = exit_status);
-        Log::success(msg.to_string()).send_to_app_log();
-        emit(&app_handle_wait, "run:status", "stopped");
+pub fn run_script(
+    path: String,
+    profile: Option<String>,
+    args: Option<Vec<String>>,
+    env: Option<BTreeMap<String, String>>,
+    cwd: Option<String>,
+    watchdog: Option<Watchdog>,
+) -> CommandResult<RunId> {
+    let watchdog = match watchdog {
+        Some(watchdog) => watchdog,
+        None => App::try_with_project(|project| project.run_watchdog(profile.as_deref()))??,
     };
+    let options = App::try_with_project(|project| -> Result<RunOptions, Error> {
+        let mut options = project.run_options(profile.as_deref())?;
+        if let Some(args) = args {
+            options.args = args;
+        }
+        if let Some(env) = env {
+            options.env.extend(env);
+        }
+        if let Some(cwd) = cwd {
+            options.cwd = Some(project.working_dir(cwd)?);
+        }
+        Ok(options)
+    })??;
+    let id = run::start(RunRequest {
+        path,
+        profile,
+        options,
+        watchdog,
+    })?;
+    Ok(id)
+}
 
-    if let Err(error) = App::with_capturer(|capturer| {
-        capturer.clear_frame();
-        capturer.start_background()
-    }) {
-        Log::error(error.to_string()).send_to_app_log();
-        return;
+/// 停止指定的运行，先请求脚本退出，超过设置的等待时间后强制结束
+#[tauri::command]
+pub fn stop_script(run_id: RunId) -> CommandResult<()> {
+    let grace = App::with_config(|config| config.stop.grace());
+    if !App::with_runs(|runs| runs.stop(run_id, grace))? {
+        Log::error(t!("No script is running.")).send_to_app_log();
     }
+    Ok(())
+}
 
-    thread::spawn(move || {
-        loop {
-            if App::get_frame_arc().is_ok() {
-                break;
-            }
-            thread::sleep(Duration::from_millis(10));
-        }
+#[tauri::command]
+pub fn list_runs() -> Vec<RunInfo> {
+    App::with_runs(|runs| runs.list())
+}
 
-        let port = App::with_config(|config| config.app.websocket_port);
-        let _ = App::try_with_project(|project| {
-            if let Err(error) = project
-                .interpreter
-                .run(path, port, on_spawned, on_stdout, on_stderr, on_exit)
-            {
-                Log::error(error.to_string()).send_to_app_log();
-            }
-        });
+#[tauri::command]
+pub fn get_run_log(run_id: RunId) -> CommandResult<Vec<RunLogLine>> {
+    Ok(App::with_runs(|runs| runs.get_log(run_id))?)
+}
 
-        App::with_capturer(|capturer| capturer.stop());
+/// 停止录制器和截图，并从录制文件生成脚本
+pub(crate) fn finish_recorder() {
+    let file = App::with_recorder(|recorder| {
+        recorder.stop();
+        recorder.take_file()
     });
+    App::with_capturer(|capturer| {
+        if capturer.is_running() {
+            capturer.stop();
+        }
+    });
+    let finished = file.map(|file| App::try_with_project(|project| project.finish_record(&file)));
+    if let Some(Ok(Err(error))) = finished {
+        let msg = t!("Recorder error occurred.", error = error.to_string());
+        Log::error(msg.to_string()).send_to_app_log();
+    }
+    Log::success(t!("Recorder has stopped.")).send_to_app_log();
 }
 
 #[tauri::command]
@@ -261,29 +269,27 @@ pub fn stop_all(app: AppHandle) {
     }
 
     if App::with_recorder(|recorder| recorder.is_running()) {
-        App::with_recorder(|recorder| {
-            recorder.stop();
-            Log::success(t!("Recorder has stopped.")).send_to_app_log();
-            emit(&app_handle, "run:status", "stopped");
-        });
+        finish_recorder();
+        emit(&app_handle, "run:status", "stopped");
         return;
     }
 
-    App::with_capturer(|capturer| {
-        if capturer.is_running() {
-            capturer.stop();
-        }
-    });
-
-    let _ = App::try_with_project(|project| {
-        if project.interpreter.get_pid() != 0 {
-            project.interpreter.stop();
-        } else {
-            Log::error(t!("No script is running.")).send_to_app_log();
-        }
-    });
+    if App::with_replayer(|replayer| replayer.is_running()) {
+        App::with_replayer(|replayer| replayer.stop());
+        return;
+    }
 
-    emit(&app_handle, "run:status", "stopped");
+    // 截图在最后一个脚本结束时停止
+    let grace = App::with_config(|config| config.stop.grace());
+    if App::with_runs(|runs| runs.stop_all(grace)) == 0 {
+        App::with_capturer(|capturer| {
+            if capturer.is_running() {
+                capturer.stop();
+            }
+        });
+        Log::error(t!("No script is running.")).send_to_app_log();
+        emit(&app_handle, "run:status", "stopped");
+    }
 }
 
 #[tauri::command]
@@ -305,6 +311,22 @@ pub fn run_recorder(app: AppHandle) {
         return;
     }
 
+    let hotkeys = match App::with_config(|config| {
+        let recorder = &config.recorder;
+        RecordHotkeys::new(
+            &recorder.pause,
+            &recorder.resume,
+            &recorder.marker,
+            &[recorder.get_start(), recorder.get_stop()],
+        )
+    }) {
+        Ok(hotkeys) => hotkeys,
+        Err(error) => {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    };
+
     Log::success(t!("Recorder is now running.")).send_to_app_log();
 
     let last_call_time = Arc::new(Mutex::new(Instant::now()));
@@ -315,6 +337,23 @@ pub fn run_recorder(app: AppHandle) {
             return;
         }
     };
+    App::with_recorder(|recorder| recorder.set_file(file.clone()));
+
+    // 同时以 JSONL 格式保存录制事件，便于回放和转换为其他语言的脚本
+    let origin = match App::try_with_project(|project| project.config.record.origin) {
+        Ok(origin) => origin.unwrap_or(Point::new(0, 0)),
+        Err(error) => {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    };
+    let event_writer = match EventWriter::new(file.with_extension(RECORD_EVENTS_SUFFIX), origin) {
+        Ok(writer) => Arc::new(Mutex::new(writer)),
+        Err(error) => {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    };
     let file_name = match file.file_name().and_then(|f| f.to_str()) {
         Some(name) => name,
         None => {
@@ -325,52 +364,156 @@ pub fn run_recorder(app: AppHandle) {
 
     emit(&app_handle, "update:record_file", file_name);
 
-    let file = match File::create(&file) {
-        Ok(f) => f,
+    // 按下鼠标按钮时截取模板图片，录制期间需要在后台截屏
+    let record_name = file
+        .file_stem()
+        .and_then(|f| f.to_str())
+        .unwrap_or_default();
+    let image_recorder = match App::try_with_project(|project| {
+        let config = &project.config.record;
+        config.capture_images.then(|| {
+            ImageRecorder::new(
+                &project.path,
+                record_name,
+                config.image_size,
+                config.image_threshold,
+            )
+        })
+    }) {
+        Ok(image_recorder) => image_recorder,
         Err(error) => {
             Log::error(error.to_string()).send_to_app_log();
             return;
         }
     };
-    let writer = BufWriter::new(file);
-    let writer = Arc::new(Mutex::new(writer));
+    if image_recorder.is_some() {
+        if let Err(error) = App::with_capturer(|capturer| {
+            capturer.clear_frame();
+            capturer.start_background()
+        }) {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    }
+    let image_recorder = Mutex::new(image_recorder);
 
-    //write header
-    {
-        let template = match App::try_with_project(|project| project.get_recorder_header_template())
-        {
-            Ok(template) => template,
-            Err(error) => {
-                Log::error(error.to_string()).send_to_app_log();
-                return;
-            }
-        };
-        let mut writer = writer.lock().unwrap();
+    // 只录制指定区域内的鼠标输入
+    let region = match App::try_with_project(|project| {
+        let config = &project.config.record;
+        RegionFilter::new(config.region, config.exclude_self)
+    }) {
+        Ok(region) => region,
+        Err(error) => {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    };
 
-        if let Err(error) = writeln!(writer, "{}", template) {
+    let code = match App::try_with_project(|project| project.interpreter.get_code()) {
+        Ok(code) => code,
+        Err(error) => {
+            Log::error(error.to_string()).send_to_app_log();
+            return;
+        }
+    };
+    let writer = match ScriptWriter::new(&file, code) {
+        Ok(writer) => Arc::new(Mutex::new(writer)),
+        Err(error) => {
             Log::error(error.to_string()).send_to_app_log();
+            return;
         }
-    }
+    };
 
-    //write token line
     {
         let error_handler = |e: Error| {
             let msg = t!("Recorder error occurred.", error = e.to_string());
             Log::error(msg.to_string()).send_to_app_log();
         };
-        let token_handler = match App::try_with_project(|project| {
-            project.get_recorder_token_handler(Box::new(error_handler))
-        }) {
-            Ok(f) => f,
-            Err(e) => {
-                let msg = t!("Recorder error occurred.", error = e.to_string());
-                Log::error(msg.to_string()).send_to_app_log();
-                return;
+
+        // 录制事件的时间为各次输入间隔之和，暂停期间的时长不计入
+        let elapsed = Arc::new(Mutex::new(0u64));
+        let elapsed_marker = Arc::clone(&elapsed);
+        let event_writer_marker = Arc::clone(&event_writer);
+        let token_handler = move |last_call_time: Arc<Mutex<Instant>>,
+                                  inputs: Vec<RecordInput>,
+                                  writer: Arc<Mutex<ScriptWriter>>|
+              -> Result<(), Error> {
+            let delta = {
+                let mut last_time = last_call_time.lock().unwrap();
+                let now = Instant::now();
+                let delta = now.duration_since(*last_time).as_millis() as u64;
+                *last_time = now;
+                delta
+            };
+            let time = {
+                let mut elapsed = elapsed.lock().unwrap();
+                *elapsed += delta;
+                *elapsed
+            };
+            let pressed = inputs
+                .iter()
+                .any(|input| matches!(input, RecordInput::Token(Token::Button(_, Press))));
+            let image = match image_recorder.lock().unwrap().as_mut() {
+                Some(image_recorder) if pressed => {
+                    let point = App::input().get_mouse_location();
+                    // 截图失败不影响录制
+                    image_recorder
+                        .capture(time, &point)
+                        .map_err(error_handler)
+                        .ok()
+                        .flatten()
+                }
+                _ => None,
+            };
+            for event in image.into_iter().chain(
+                inputs
+                    .iter()
+                    .filter_map(|input| RecordEvent::from_input(time, input)),
+            ) {
+                event_writer.lock().unwrap().write(&event)?;
+            }
+            let mut writer = writer.lock().unwrap();
+            writer.write_sleep(delta)?;
+            writer.write_inputs(&inputs)
+        };
+
+        let writer_marker = Arc::clone(&writer);
+        let last_call_time_marker = Arc::clone(&last_call_time);
+        let markers = AtomicU32::new(0);
+        let action_handler = move |action: RecordAction| match action {
+            RecordAction::Pause => Log::info(t!("Recorder is paused.")).send_to_app_log(),
+            RecordAction::Resume => Log::info(t!("Recorder is resumed.")).send_to_app_log(),
+            RecordAction::Marker => {
+                let label = format!("marker {}", markers.fetch_add(1, Ordering::SeqCst) + 1);
+                let time = *elapsed_marker.lock().unwrap()
+                    + last_call_time_marker.lock().unwrap().elapsed().as_millis() as u64;
+                let event = RecordEvent::new(
+                    time,
+                    RecordEventKind::Marker {
+                        label: label.clone(),
+                    },
+                );
+                let result = event_writer_marker
+                    .lock()
+                    .unwrap()
+                    .write(&event)
+                    .and_then(|_| writer_marker.lock().unwrap().write_comment(&label));
+                if let Err(e) = result {
+                    error_handler(e);
+                }
+                Log::info(t!("Recorder marker is inserted.", label = label)).send_to_app_log();
             }
         };
 
         App::with_recorder(|recorder| {
-            recorder.start(last_call_time, token_handler, writer);
+            recorder.start(
+                last_call_time,
+                HotkeyFilter::new(hotkeys),
+                region,
+                token_handler,
+                action_handler,
+                writer,
+            );
         });
     }
 }
@@ -0,0 +1,63 @@
+use super::{CommandResult, utils::emit};
+use crate::app::{App, Log};
+use crate::record::{ReplayOptions, read_events};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn replay_record(
+    app_handle: AppHandle,
+    file_name: String,
+    speed: Option<f64>,
+    loops: Option<u32>,
+) -> CommandResult<()> {
+    let app_handle = Arc::new(app_handle);
+    let path = App::try_with_project(|project| project.path.join(&file_name))?;
+
+    if App::with_recorder(|recorder| recorder.is_running()) {
+        return Err(t!("Recorder is running. Please stop it first.").into());
+    }
+
+    let events = read_events(&path)?;
+    let default = ReplayOptions::default();
+    let origin = App::try_with_project(|project| project.config.record.origin)?;
+    let options = ReplayOptions {
+        speed: speed.unwrap_or(default.speed),
+        loops: loops.unwrap_or(default.loops),
+        origin: origin.unwrap_or(default.origin),
+    };
+    let app_handle_finished = Arc::clone(&app_handle);
+    App::with_replayer(|replayer| {
+        replayer.start(events, options, move |result| {
+            match result {
+                Ok(()) => Log::success(t!("Replay has stopped.")).send_to_app_log(),
+                Err(error) => {
+                    let msg = t!("Replay error occurred.", error = error.to_string());
+                    Log::error(msg.to_string()).send_to_app_log();
+                }
+            }
+            emit(&app_handle_finished, "run:status", "stopped");
+        })
+    })?;
+
+    Log::success(t!("Replay is now running.")).send_to_app_log();
+    emit(&app_handle, "run:status", "running");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_replay() {
+    App::with_replayer(|replayer| replayer.stop());
+}
+
+/// 将录制事件文件转换为当前项目语言的脚本，返回新脚本的文件名
+#[tauri::command]
+pub fn convert_record(file_name: String) -> CommandResult<String> {
+    let path = App::try_with_project(|project| {
+        project.convert_record_events(project.path.join(&file_name))
+    })??;
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Err(t!("Invalid file name.").into());
+    };
+    Ok(name.to_string())
+}
@@ -0,0 +1,39 @@
+use super::CommandResult;
+use crate::{
+    app::App,
+    schedule::{Schedule, ScheduleInfo},
+};
+use anyhow::Error;
+
+#[tauri::command]
+pub fn list_schedules() -> Vec<ScheduleInfo> {
+    App::with_scheduler(|scheduler| scheduler.list())
+}
+
+/// 保存定时运行到项目配置并重新载入，同名时替换，其他定时运行不受影响
+#[tauri::command]
+pub fn save_schedule(schedule: Schedule) -> CommandResult<()> {
+    let schedules = App::try_with_project_mut(|project| -> Result<Vec<Schedule>, Error> {
+        project.save_schedule(schedule)?;
+        Ok(project.config.schedules.clone())
+    })??;
+    App::with_scheduler(|scheduler| scheduler.update(schedules));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_schedule(name: String) -> CommandResult<()> {
+    let schedules = App::try_with_project_mut(|project| -> Result<Vec<Schedule>, Error> {
+        project.remove_schedule(&name)?;
+        Ok(project.config.schedules.clone())
+    })??;
+    App::with_scheduler(|scheduler| scheduler.update(schedules));
+    Ok(())
+}
+
+/// 立即运行定时运行，关闭了的定时运行也可以手动运行
+#[tauri::command]
+pub fn run_schedule(name: String) -> CommandResult<()> {
+    App::with_scheduler(|scheduler| scheduler.trigger(&name))?;
+    Ok(())
+}
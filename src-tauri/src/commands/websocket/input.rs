@@ -1,54 +1,159 @@
-use super::utils::{get_required_i32, get_required_point, get_required_string};
+use super::utils::{
+    get_optional_f64, get_optional_string, get_optional_u64, get_project_dir, get_required_bool,
+    get_required_i32, get_required_point, get_required_string, get_required_u64,
+};
 use crate::app::App;
-use anyhow::Result;
+use crate::input::{
+    Button, Curve, DEFAULT_CLICK_INTERVAL_MILLIS, DEFAULT_DRAG_DURATION_MILLIS,
+    DEFAULT_HOTKEY_INTERVAL_MILLIS, Humanize, Mode, Motion, Region, RegionPolicy, TextStrategy,
+    clipboard, parse_button,
+};
+use anyhow::{Result, anyhow};
 use serde_json::Value;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr as _,
+    time::Duration,
+};
+
+/// 输入模式、允许区域、拟人化和演练模式由所有脚本共用，
+/// 同时运行多个脚本时不允许修改，避免一个脚本改变其他脚本的输入
+fn ensure_single_run() -> Result<()> {
+    if App::with_runs(|runs| runs.active_count()) > 1 {
+        return Err(anyhow!(t!(
+            "Input settings cannot be changed while other scripts are running."
+        )));
+    }
+    Ok(())
+}
+
+fn get_button(args: &Value) -> Result<Button> {
+    match get_optional_string(args, "button")? {
+        Some(button) => parse_button(&button),
+        None => Ok(Button::Left),
+    }
+}
+
+fn get_click_interval(args: &Value) -> Result<Duration> {
+    let millis = get_optional_u64(args, "interval_ms")?.unwrap_or(DEFAULT_CLICK_INTERVAL_MILLIS);
+    Ok(Duration::from_millis(millis))
+}
 
 pub fn click_left(_args: Value) -> Result<Value> {
-    App::input().click_left();
+    App::input().click_left()?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn click_right(_args: Value) -> Result<Value> {
-    App::input().click_right();
+    App::input().click_right()?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn click_middle(_args: Value) -> Result<Value> {
+    App::input().click_middle()?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn double_click(args: Value) -> Result<Value> {
+    let button = get_button(&args)?;
+    let interval = get_click_interval(&args)?;
+    App::input().double_click(button, interval)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn click_at(args: Value) -> Result<Value> {
+    let point = get_required_point(&args, "absolute_point")?;
+    let button = get_button(&args)?;
+    let count = get_optional_u64(&args, "count")?.unwrap_or(1) as u32;
+    let interval = get_click_interval(&args)?;
+    App::input().click_at(&point, button, count, interval)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn drag(args: Value) -> Result<Value> {
+    let from = get_required_point(&args, "from")?;
+    let to = get_required_point(&args, "to")?;
+    let button = get_button(&args)?;
+    let duration_millis =
+        get_optional_u64(&args, "duration_ms")?.unwrap_or(DEFAULT_DRAG_DURATION_MILLIS);
+    App::input().drag(&from, &to, button, duration_millis)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn press_left(_args: Value) -> Result<Value> {
-    App::input().press_left();
+    App::input().press_left()?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn press_right(_args: Value) -> Result<Value> {
-    App::input().press_right();
+    App::input().press_right()?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn press_middle(_args: Value) -> Result<Value> {
+    App::input().press_middle()?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn release_left(_args: Value) -> Result<Value> {
-    App::input().release_left();
+    App::input().release_left()?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn release_right(_args: Value) -> Result<Value> {
-    App::input().release_right();
+    App::input().release_right()?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn release_middle(_args: Value) -> Result<Value> {
+    App::input().release_middle()?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn move_absolute(args: Value) -> Result<Value> {
     let point = get_required_point(&args, "absolute_point")?;
-    App::input().move_absolute(&point);
+    App::input().move_absolute(&point)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn move_to(args: Value) -> Result<Value> {
+    let point = get_required_point(&args, "absolute_point")?;
+    let duration_millis = get_required_u64(&args, "duration_ms")?;
+    let curve = match get_optional_string(&args, "curve")? {
+        Some(curve) => Curve::from_str(&curve)?,
+        None => Curve::Linear,
+    };
+    let jitter = get_optional_f64(&args, "jitter")?.unwrap_or(0.0);
+    let overshoot = get_optional_f64(&args, "overshoot")?.unwrap_or(0.0);
+    let motion = Motion::new(curve, jitter, overshoot);
+    App::input().move_to(&point, duration_millis, &motion)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn move_relative(args: Value) -> Result<Value> {
     let point = get_required_point(&args, "offset")?;
-    App::input().move_relative(&point);
+    App::input().move_relative(&point)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn get_mouse_location(_args: Value) -> Result<Value> {
     let point = App::input().get_mouse_location();
     Ok(serde_json::to_value(point)?)
 }
+pub fn get_mouse_buttons(_args: Value) -> Result<Value> {
+    let buttons = App::input().get_mouse_buttons();
+    Ok(serde_json::to_value(buttons)?)
+}
+pub fn get_pressed_keys(_args: Value) -> Result<Value> {
+    let keys: Vec<String> = App::input()
+        .get_pressed_keys()
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    Ok(serde_json::to_value(keys)?)
+}
+pub fn is_key_pressed(args: Value) -> Result<Value> {
+    let key = get_required_string(&args, "key")?;
+    let pressed = App::input().is_key_pressed(key)?;
+    Ok(serde_json::to_value(pressed)?)
+}
+pub fn get_lock_keys(_args: Value) -> Result<Value> {
+    let lock_keys = App::input().get_lock_keys()?;
+    Ok(serde_json::to_value(lock_keys)?)
+}
 pub fn scroll_vertical(args: Value) -> Result<Value> {
     let length = get_required_i32(&args, "length")?;
-    App::input().scroll_vertical(length);
+    App::input().scroll_vertical(length)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn scroll_horizontal(args: Value) -> Result<Value> {
     let length = get_required_i32(&args, "length")?;
-    App::input().scroll_horizontal(length);
+    App::input().scroll_horizontal(length)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
 pub fn press_key(args: Value) -> Result<Value> {
@@ -66,8 +171,115 @@ pub fn click_key(args: Value) -> Result<Value> {
     App::input().click_key(&key)?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
+pub fn hotkey(args: Value) -> Result<Value> {
+    let keys = get_required_string(&args, "keys")?;
+    let hold = Duration::from_millis(get_optional_u64(&args, "hold_ms")?.unwrap_or(0));
+    let interval = Duration::from_millis(
+        get_optional_u64(&args, "interval_ms")?.unwrap_or(DEFAULT_HOTKEY_INTERVAL_MILLIS),
+    );
+    App::input().hotkey(&keys, hold, interval)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
 pub fn input_text(args: Value) -> Result<Value> {
     let text = get_required_string(&args, "text")?;
-    App::input().input_text(&text);
+    let strategy = match get_optional_string(&args, "strategy")? {
+        Some(strategy) => TextStrategy::from_str(&strategy)?,
+        None => TextStrategy::default(),
+    };
+    App::input().input_text_with(&text, strategy)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn get_clipboard_text(_args: Value) -> Result<Value> {
+    let text = clipboard::read_text()?;
+    Ok(serde_json::to_value(text)?)
+}
+pub fn set_clipboard_text(args: Value) -> Result<Value> {
+    let text = get_required_string(&args, "text")?;
+    clipboard::write_text(text)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn get_clipboard_image(_args: Value) -> Result<Value> {
+    let base64_png = clipboard::read_image()?.to_base64_png()?;
+    Ok(serde_json::to_value(base64_png)?)
+}
+pub fn set_input_mode(args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    let mode = get_required_string(&args, "mode")?;
+    App::input().set_mode(Mode::from_str(&mode)?);
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn get_input_mode(_args: Value) -> Result<Value> {
+    let mode = App::input().get_mode();
+    Ok(serde_json::to_value(mode)?)
+}
+pub fn set_input_dry_run(args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    let enabled = get_required_bool(&args, "enabled")?;
+    if !enabled {
+        App::input().stop_dry_run();
+        return Ok(serde_json::to_value(None::<i32>)?);
+    }
+    let path = match get_optional_string(&args, "path")? {
+        Some(path) if Path::new(&path).is_absolute() => Some(PathBuf::from(path)),
+        Some(path) => Some(get_project_dir()?.join(path)),
+        None => None,
+    };
+    App::input().start_dry_run(path.as_deref())?;
     Ok(serde_json::to_value(None::<i32>)?)
 }
+pub fn get_input_log(_args: Value) -> Result<Value> {
+    let events = App::input().get_input_log();
+    Ok(serde_json::to_value(events)?)
+}
+pub fn clear_input_log(_args: Value) -> Result<Value> {
+    App::input().clear_input_log();
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn set_input_region(args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    let start_point = get_required_point(&args, "start_point")?;
+    let end_point = get_required_point(&args, "end_point")?;
+    let policy = match get_optional_string(&args, "policy")? {
+        Some(policy) => RegionPolicy::from_str(&policy)?,
+        None => RegionPolicy::default(),
+    };
+    let region = Region::new(start_point, end_point, policy);
+    App::input().set_region(Some(region))?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn clear_input_region(_args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    App::input().set_region(None)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn get_input_region(_args: Value) -> Result<Value> {
+    let region = App::input().get_region();
+    Ok(serde_json::to_value(region)?)
+}
+pub fn set_input_humanize(args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    let default = Humanize::default();
+    let humanize = Humanize {
+        click_hold_min_ms: get_optional_u64(&args, "click_hold_min_ms")?
+            .unwrap_or(default.click_hold_min_ms),
+        click_hold_max_ms: get_optional_u64(&args, "click_hold_max_ms")?
+            .unwrap_or(default.click_hold_max_ms),
+        key_delay_min_ms: get_optional_u64(&args, "key_delay_min_ms")?
+            .unwrap_or(default.key_delay_min_ms),
+        key_delay_max_ms: get_optional_u64(&args, "key_delay_max_ms")?
+            .unwrap_or(default.key_delay_max_ms),
+        click_offset: get_optional_f64(&args, "click_offset")?.unwrap_or(default.click_offset),
+        seed: get_optional_u64(&args, "seed")?,
+    };
+    App::input().set_humanize(Some(humanize))?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn clear_input_humanize(_args: Value) -> Result<Value> {
+    ensure_single_run()?;
+    App::input().set_humanize(None)?;
+    Ok(serde_json::to_value(None::<i32>)?)
+}
+pub fn get_input_humanize(_args: Value) -> Result<Value> {
+    let humanize = App::input().get_humanize();
+    Ok(serde_json::to_value(humanize)?)
+}
@@ -50,6 +50,73 @@ pub fn get_required_f64(args: &Value, key: &str) -> Result<f64> {
         })
 }
 
+pub fn get_required_u64(args: &Value, key: &str) -> Result<u64> {
+    args.get(key)
+        .ok_or_else(|| anyhow!(t!("WebSocket argument is missing.", argument = key)))
+        .and_then(|v| {
+            serde_json::from_value(v.clone()).map_err(|e| {
+                anyhow!(t!(
+                    "Failed to deserialize WebSocket argument.",
+                    argument = key,
+                    error = e.to_string()
+                ))
+            })
+        })
+}
+
+pub fn get_required_bool(args: &Value, key: &str) -> Result<bool> {
+    args.get(key)
+        .ok_or_else(|| anyhow!(t!("WebSocket argument is missing.", argument = key)))
+        .and_then(|v| {
+            serde_json::from_value(v.clone()).map_err(|e| {
+                anyhow!(t!(
+                    "Failed to deserialize WebSocket argument.",
+                    argument = key,
+                    error = e.to_string()
+                ))
+            })
+        })
+}
+
+pub fn get_optional_string(args: &Value, key: &str) -> Result<Option<String>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => serde_json::from_value(v.clone()).map(Some).map_err(|e| {
+            anyhow!(t!(
+                "Failed to deserialize WebSocket argument.",
+                argument = key,
+                error = e.to_string()
+            ))
+        }),
+    }
+}
+
+pub fn get_optional_u64(args: &Value, key: &str) -> Result<Option<u64>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => serde_json::from_value(v.clone()).map(Some).map_err(|e| {
+            anyhow!(t!(
+                "Failed to deserialize WebSocket argument.",
+                argument = key,
+                error = e.to_string()
+            ))
+        }),
+    }
+}
+
+pub fn get_optional_f64(args: &Value, key: &str) -> Result<Option<f64>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => serde_json::from_value(v.clone()).map(Some).map_err(|e| {
+            anyhow!(t!(
+                "Failed to deserialize WebSocket argument.",
+                argument = key,
+                error = e.to_string()
+            ))
+        }),
+    }
+}
+
 pub fn get_required_hexcolor(args: &Value, key: &str) -> Result<HexColor> {
     args.get(key)
         .ok_or_else(|| anyhow!(t!("WebSocket argument is missing.", argument = key)))
@@ -0,0 +1,184 @@
+use crate::{
+    app::{App, ConfigFailsafe, Log},
+    commands::frontend::{project::finish_recorder, utils::emit},
+    input::{Chord, Key, SystemKey},
+    types::{Point, Size},
+};
+use anyhow::Result;
+use device_query::{DeviceQuery as _, DeviceState};
+use display_info::DisplayInfo;
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+const POLL_INTERVAL_MILLIS: u64 = 30;
+/// 距离屏幕角落多少像素以内算作进入角落
+const CORNER_MARGIN: i32 = 2;
+
+/// 脚本运行期间在后台监听紧急停止热键和屏幕角落
+pub struct Engine {
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl Engine {
+    pub fn start(&self, config: &ConfigFailsafe) -> Result<()> {
+        self.stop();
+        let chord = config.chord()?;
+        let chord = Chord {
+            modifiers: chord.modifiers.into_iter().map(generic).collect(),
+            key: generic(chord.key),
+        };
+        let corners = if config.corner {
+            Some(outer_corners(&monitor_bounds()?))
+        } else {
+            None
+        };
+        let running = Arc::new(AtomicBool::new(true));
+        *self.running.lock().unwrap() = Some(Arc::clone(&running));
+
+        thread::spawn(move || {
+            let device_state = DeviceState::new();
+            // 脚本开始时鼠标可能已经在角落，必须先离开角落一次才会触发
+            let mut corner_armed = false;
+            while running.load(Ordering::SeqCst) {
+                let pressed: Vec<SystemKey> = device_state
+                    .get_keys()
+                    .into_iter()
+                    .filter_map(|keycode| Key::try_from(keycode).ok())
+                    .map(|key| SystemKey::from(key.generic()))
+                    .collect();
+                let hotkey_pressed = chord
+                    .modifiers
+                    .iter()
+                    .chain([&chord.key])
+                    .all(|key| pressed.contains(key));
+                let in_corner = corners.as_ref().is_some_and(|corners| {
+                    let (x, y) = device_state.get_mouse().coords;
+                    is_in_corner(&Point::new(x, y), corners)
+                });
+                if !in_corner {
+                    corner_armed = true;
+                }
+                if hotkey_pressed || (corner_armed && in_corner) {
+                    running.store(false, Ordering::SeqCst);
+                    emergency_stop();
+                    break;
+                }
+                thread::sleep(Duration::from_millis(POLL_INTERVAL_MILLIS));
+            }
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        if let Some(running) = self.running.lock().unwrap().take() {
+            running.store(false, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|running| running.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+}
+
+/// 停止脚本、录制器和回放，丢弃未执行的输入并松开所有按键
+pub fn emergency_stop() {
+    App::with_runs(|runs| runs.stop_all(Duration::ZERO));
+    // 与手动停止相同，停止录制后生成脚本
+    if App::with_recorder(|recorder| recorder.is_running()) {
+        finish_recorder();
+    }
+    App::with_replayer(|replayer| replayer.stop());
+    App::with_capturer(|capturer| {
+        if capturer.is_running() {
+            capturer.stop();
+        }
+    });
+    if let Err(error) = App::input().release_all() {
+        Log::error(error.to_string()).send_to_app_log();
+    }
+    Log::warn(t!("Emergency stop triggered.")).send_to_app_log();
+    emit(&App::get_app_handle(), "run:status", "stopped");
+}
+
+// 热键和按下的键都不区分左右的修饰键，按右侧的修饰键也能触发
+fn generic(key: SystemKey) -> SystemKey {
+    Key::try_from(key)
+        .map(|key| SystemKey::from(key.generic()))
+        .unwrap_or(key)
+}
+
+// 每个显示器的左上角和大小，与鼠标位置使用相同的坐标系
+fn monitor_bounds() -> Result<Vec<(Point, Size)>> {
+    let monitors = DisplayInfo::all()?
+        .into_iter()
+        .map(|info| {
+            let origin = Point::new(info.x, info.y);
+            let size = Size {
+                width: info.width,
+                height: info.height,
+            };
+            (origin, size)
+        })
+        .collect();
+    Ok(monitors)
+}
+
+// 显示器的四个角中，向外相邻的位置都不在任何显示器上的才是桌面的外角。
+// 相邻显示器共用的边上的角不算，鼠标在显示器之间移动时不会触发
+fn outer_corners(monitors: &[(Point, Size)]) -> Vec<Point> {
+    let on_desktop = |point: Point| {
+        monitors
+            .iter()
+            .any(|(origin, size)| contains(origin, size, &point))
+    };
+    monitors
+        .iter()
+        .flat_map(|(origin, size)| {
+            let (right, bottom) = (
+                origin.x + size.width as i32 - 1,
+                origin.y + size.height as i32 - 1,
+            );
+            [
+                (origin.x, origin.y, -1, -1),
+                (right, origin.y, 1, -1),
+                (origin.x, bottom, -1, 1),
+                (right, bottom, 1, 1),
+            ]
+        })
+        .filter(|&(x, y, dx, dy)| {
+            !on_desktop(Point::new(x + dx, y))
+                && !on_desktop(Point::new(x, y + dy))
+                && !on_desktop(Point::new(x + dx, y + dy))
+        })
+        .map(|(x, y, _, _)| Point::new(x, y))
+        .collect()
+}
+
+fn contains(origin: &Point, size: &Size, point: &Point) -> bool {
+    (origin.x..origin.x + size.width as i32).contains(&point.x)
+        && (origin.y..origin.y + size.height as i32).contains(&point.y)
+}
+
+fn is_in_corner(point: &Point, corners: &[Point]) -> bool {
+    corners.iter().any(|corner| {
+        (point.x - corner.x).abs() <= CORNER_MARGIN && (point.y - corner.y).abs() <= CORNER_MARGIN
+    })
+}
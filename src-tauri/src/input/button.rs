@@ -0,0 +1,24 @@
+use super::Button;
+use anyhow::{Result, anyhow};
+
+/// 双击、三击时两次点击之间的默认间隔（毫秒）
+pub const DEFAULT_CLICK_INTERVAL_MILLIS: u64 = 50;
+
+/// 拖拽的默认时长（毫秒）
+pub const DEFAULT_DRAG_DURATION_MILLIS: u64 = 300;
+
+/// 将 websocket 传入的按钮名称转换为按钮
+pub fn parse_button(name: impl AsRef<str>) -> Result<Button> {
+    let button = match name.as_ref().to_lowercase().as_str() {
+        "left" => Button::Left,
+        "right" => Button::Right,
+        "middle" => Button::Middle,
+        unknown_button => {
+            return Err(anyhow!(t!(
+                "The mouse button is not supported.",
+                button = unknown_button
+            )));
+        }
+    };
+    Ok(button)
+}
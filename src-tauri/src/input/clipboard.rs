@@ -0,0 +1,75 @@
+use crate::app::App;
+use crate::capture::Frame;
+use anyhow::{Result, anyhow};
+use tauri::image::Image;
+use tauri_plugin_clipboard_manager::ClipboardExt as _;
+
+/// 剪贴板中的内容，用于粘贴文本后恢复剪贴板
+pub enum Content {
+    Text(String),
+    Image(Frame),
+    Empty,
+}
+
+fn clipboard_error(error: impl ToString) -> anyhow::Error {
+    anyhow!(t!(
+        "Failed to access the clipboard.",
+        error = error.to_string()
+    ))
+}
+
+pub fn read_text() -> Result<String> {
+    App::get_app_handle()
+        .clipboard()
+        .read_text()
+        .map_err(clipboard_error)
+}
+
+pub fn write_text(text: impl Into<String>) -> Result<()> {
+    App::get_app_handle()
+        .clipboard()
+        .write_text(text.into())
+        .map_err(clipboard_error)
+}
+
+pub fn read_image() -> Result<Frame> {
+    let image = App::get_app_handle()
+        .clipboard()
+        .read_image()
+        .map_err(clipboard_error)?;
+    Ok(Frame::new(
+        image.width(),
+        image.height(),
+        image.rgba().to_vec(),
+    ))
+}
+
+pub fn write_image(frame: &Frame) -> Result<()> {
+    let image = Image::new(&frame.buffer, frame.width, frame.height);
+    App::get_app_handle()
+        .clipboard()
+        .write_image(&image)
+        .map_err(clipboard_error)
+}
+
+/// 读取当前剪贴板内容，读取失败时视为空
+pub fn snapshot() -> Content {
+    if let Ok(text) = read_text() {
+        return Content::Text(text);
+    }
+    if let Ok(frame) = read_image() {
+        return Content::Image(frame);
+    }
+    Content::Empty
+}
+
+pub fn restore(content: Content) -> Result<()> {
+    match content {
+        Content::Text(text) => write_text(text),
+        Content::Image(frame) => write_image(&frame),
+        Content::Empty => App::get_app_handle()
+            .clipboard()
+            .clear()
+            .map_err(clipboard_error),
+    }
+}
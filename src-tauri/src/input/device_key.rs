@@ -0,0 +1,113 @@
+use crate::input::Key;
+use anyhow::{Result, anyhow};
+use device_query::Keycode;
+
+impl TryFrom<Keycode> for Key {
+    type Error = anyhow::Error;
+
+    fn try_from(keycode: Keycode) -> Result<Self, Self::Error> {
+        let key = match keycode {
+            Keycode::Key0 => Key::Num0,
+            Keycode::Key1 => Key::Num1,
+            Keycode::Key2 => Key::Num2,
+            Keycode::Key3 => Key::Num3,
+            Keycode::Key4 => Key::Num4,
+            Keycode::Key5 => Key::Num5,
+            Keycode::Key6 => Key::Num6,
+            Keycode::Key7 => Key::Num7,
+            Keycode::Key8 => Key::Num8,
+            Keycode::Key9 => Key::Num9,
+            Keycode::A => Key::A,
+            Keycode::B => Key::B,
+            Keycode::C => Key::C,
+            Keycode::D => Key::D,
+            Keycode::E => Key::E,
+            Keycode::F => Key::F,
+            Keycode::G => Key::G,
+            Keycode::H => Key::H,
+            Keycode::I => Key::I,
+            Keycode::J => Key::J,
+            Keycode::K => Key::K,
+            Keycode::L => Key::L,
+            Keycode::M => Key::M,
+            Keycode::N => Key::N,
+            Keycode::O => Key::O,
+            Keycode::P => Key::P,
+            Keycode::Q => Key::Q,
+            Keycode::R => Key::R,
+            Keycode::S => Key::S,
+            Keycode::T => Key::T,
+            Keycode::U => Key::U,
+            Keycode::V => Key::V,
+            Keycode::W => Key::W,
+            Keycode::X => Key::X,
+            Keycode::Y => Key::Y,
+            Keycode::Z => Key::Z,
+            Keycode::F1 => Key::F1,
+            Keycode::F2 => Key::F2,
+            Keycode::F3 => Key::F3,
+            Keycode::F4 => Key::F4,
+            Keycode::F5 => Key::F5,
+            Keycode::F6 => Key::F6,
+            Keycode::F7 => Key::F7,
+            Keycode::F8 => Key::F8,
+            Keycode::F9 => Key::F9,
+            Keycode::F10 => Key::F10,
+            Keycode::F11 => Key::F11,
+            Keycode::F12 => Key::F12,
+            Keycode::Escape => Key::Escape,
+            Keycode::Space => Key::Space,
+            Keycode::LControl => Key::Control,
+            Keycode::RControl => Key::ControlRight,
+            Keycode::LShift => Key::Shift,
+            Keycode::RShift => Key::ShiftRight,
+            Keycode::LAlt => Key::Alt,
+            Keycode::RAlt => Key::AltRight,
+            Keycode::LMeta => Key::Meta,
+            Keycode::RMeta => Key::MetaRight,
+            Keycode::Enter => Key::Return,
+            Keycode::Up => Key::UpArrow,
+            Keycode::Down => Key::DownArrow,
+            Keycode::Left => Key::LeftArrow,
+            Keycode::Right => Key::RightArrow,
+            Keycode::Backspace => Key::Backspace,
+            Keycode::CapsLock => Key::CapsLock,
+            Keycode::Tab => Key::Tab,
+            Keycode::Home => Key::Home,
+            Keycode::End => Key::End,
+            Keycode::PageUp => Key::PageUp,
+            Keycode::PageDown => Key::PageDown,
+            Keycode::Insert => Key::Insert,
+            Keycode::Delete => Key::Delete,
+            Keycode::Numpad0 => Key::Kp0,
+            Keycode::Numpad1 => Key::Kp1,
+            Keycode::Numpad2 => Key::Kp2,
+            Keycode::Numpad3 => Key::Kp3,
+            Keycode::Numpad4 => Key::Kp4,
+            Keycode::Numpad5 => Key::Kp5,
+            Keycode::Numpad6 => Key::Kp6,
+            Keycode::Numpad7 => Key::Kp7,
+            Keycode::Numpad8 => Key::Kp8,
+            Keycode::Numpad9 => Key::Kp9,
+            Keycode::NumpadSubtract => Key::KpMinus,
+            Keycode::NumpadAdd => Key::KpPlus,
+            Keycode::NumpadDivide => Key::KpDivide,
+            Keycode::NumpadMultiply => Key::KpMultiply,
+            Keycode::Grave => Key::BackQuote,
+            Keycode::Minus => Key::Minus,
+            Keycode::Equal => Key::Equal,
+            Keycode::LeftBracket => Key::LeftBracket,
+            Keycode::RightBracket => Key::RightBracket,
+            Keycode::BackSlash => Key::BackSlash,
+            Keycode::Semicolon => Key::SemiColon,
+            Keycode::Apostrophe => Key::Quote,
+            Keycode::Comma => Key::Comma,
+            Keycode::Dot => Key::Dot,
+            Keycode::Slash => Key::Slash,
+            _ => {
+                return Err(anyhow!(t!("The key is not supported.")));
+            }
+        };
+        Ok(key)
+    }
+}
@@ -1,7 +1,12 @@
-use super::Key;
+use super::{
+    Chord, Humanize, InputEvent, Key, LockKeys, Mode, Motion, MouseButtons, Region, TextStrategy,
+    clipboard, event_log::EventLog, humanize::Humanizer, motion::MOVE_STEP_MILLIS, parse_hotkey,
+    parse_key_name, text::PASTE_RESTORE_DELAY_MILLIS, text::paste_hotkey,
+};
+use crate::app::Log;
 use crate::types::Point;
-use anyhow::Result;
-use crossbeam_channel::{Sender, unbounded};
+use anyhow::{Result, anyhow};
+use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use device_query::{DeviceQuery as _, DeviceState};
 use enigo::{
     Axis::{Horizontal, Vertical},
@@ -11,126 +16,606 @@ use enigo::{
     Enigo, Settings,
     agent::{Agent, Token},
 };
-use std::{str::FromStr as _, sync::RwLock};
-use std::{sync::LazyLock, thread};
+use std::{path::Path, str::FromStr as _, sync::RwLock};
+use std::{
+    sync::{LazyLock, Mutex},
+    thread,
+    time::Duration,
+};
 
-#[derive(Debug)]
-pub struct Engine {}
+pub struct Engine {
+    mode: RwLock<Mode>,
+    /// 演练模式：存在时只记录输入，不实际执行
+    dry_run: Mutex<Option<EventLog>>,
+    /// 允许输入的区域，超出区域的坐标会被拒绝或收拢
+    region: RwLock<Option<Region>>,
+    /// 演练模式下模拟的鼠标位置。演练时移动不会执行，按下、点击和滚动按它检查区域
+    target: Mutex<Option<Point>>,
+    /// 拟人化输入：存在时点击按住随机时长、点击位置随机偏移、逐字符随机间隔输入文本
+    humanizer: Mutex<Option<Humanizer>>,
+}
 
 impl Default for Engine {
     fn default() -> Self {
-        Self {}
+        Self {
+            mode: RwLock::new(Mode::default()),
+            dry_run: Mutex::new(None),
+            region: RwLock::new(None),
+            target: Mutex::new(None),
+            humanizer: Mutex::new(None),
+        }
     }
 }
 
 impl Engine {
-    fn send(&self, token: &Token) {
-        INPUT_SENDER.send(token.to_owned()).unwrap();
+    fn send(&self, token: &Token) -> Result<()> {
+        self.send_as(token, self.get_mode())
+    }
+
+    /// 设置了允许区域时，输入线程在执行时按当时的鼠标位置检查坐标。
+    /// 绝对移动与鼠标位置无关，发送前先检查，被拒绝时异步模式也能立即返回错误
+    fn send_as(&self, token: &Token, mode: Mode) -> Result<()> {
+        let region = self.get_region();
+        if let Some(event_log) = self.dry_run.lock().unwrap().as_mut() {
+            let token = match region.as_ref() {
+                Some(region) => self.confine_dry_run(token, region)?,
+                None => token.clone(),
+            };
+            return event_log.record(&token);
+        }
+        if let (Some(region), Token::MoveMouse(x, y, Abs)) = (region.as_ref(), token) {
+            region.confine(&Point::new(*x, *y))?;
+        }
+        match mode {
+            Mode::Async => {
+                let job = Job {
+                    token: token.to_owned(),
+                    region,
+                    ack: None,
+                };
+                INPUT_CHANNEL
+                    .0
+                    .send(job)
+                    .map_err(|_| anyhow!(t!("The input thread has stopped.")))
+            }
+            Mode::Sync => {
+                let (ack, done) = bounded(1);
+                let job = Job {
+                    token: token.to_owned(),
+                    region,
+                    ack: Some(ack),
+                };
+                INPUT_CHANNEL
+                    .0
+                    .send(job)
+                    .map_err(|_| anyhow!(t!("The input thread has stopped.")))?;
+                done.recv()
+                    .map_err(|_| anyhow!(t!("The input thread has stopped.")))?
+            }
+        }
     }
 
-    pub fn execute_token(&self, tokens: &Vec<Token>) {
+    /// 丢弃队列中尚未执行的输入，并松开所有仍处于按下状态的按键和鼠标按钮
+    pub fn release_all(&self) -> Result<()> {
+        self.reset_target();
+        while let Ok(job) = INPUT_CHANNEL.1.try_recv() {
+            if let Some(ack) = job.ack {
+                let _ = ack.send(Err(anyhow!(t!("The input was cancelled."))));
+            }
+        }
+        let held = HELD_TOKENS.lock().unwrap().clone();
+        for token in held.iter().rev() {
+            let release = match token {
+                Token::Key(key, _) => Token::Key(*key, Release),
+                Token::Button(button, _) => Token::Button(*button, Release),
+                _ => continue,
+            };
+            let (ack, done) = bounded(1);
+            let job = Job {
+                token: release,
+                region: None,
+                ack: Some(ack),
+            };
+            INPUT_CHANNEL
+                .0
+                .send(job)
+                .map_err(|_| anyhow!(t!("The input thread has stopped.")))?;
+            done.recv()
+                .map_err(|_| anyhow!(t!("The input thread has stopped.")))??;
+        }
+        Ok(())
+    }
+
+    // 演练时按模拟的鼠标位置检查，并记录移动后的位置
+    fn confine_dry_run(&self, token: &Token, region: &Region) -> Result<Token> {
+        let mut target = self.target.lock().unwrap();
+        let location = target.unwrap_or_else(|| self.get_mouse_location());
+        let confined = confine(token, region, location)?;
+        match confined {
+            Token::MoveMouse(x, y, Abs) => *target = Some(Point::new(x, y)),
+            Token::MoveMouse(x, y, Rel) => {
+                *target = Some(Point::new(location.x + x, location.y + y))
+            }
+            _ => {}
+        }
+        Ok(confined)
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        *self.region.read().unwrap()
+    }
+
+    pub fn set_region(&self, region: Option<Region>) -> Result<()> {
+        if let Some(region) = region.as_ref() {
+            if region.start_point.x > region.end_point.x
+                || region.start_point.y > region.end_point.y
+            {
+                return Err(anyhow!(t!("The input region is invalid.")));
+            }
+        }
+        *self.region.write().unwrap() = region;
+        self.reset_target();
+        Ok(())
+    }
+
+    pub fn get_humanize(&self) -> Option<Humanize> {
+        self.humanizer
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|humanizer| humanizer.settings())
+    }
+
+    /// 每次设置都会重新初始化随机数，设置了种子时可以重现同样的输入节奏
+    pub fn set_humanize(&self, humanize: Option<Humanize>) -> Result<()> {
+        if let Some(humanize) = humanize.as_ref() {
+            humanize.validate()?;
+        }
+        *self.humanizer.lock().unwrap() = humanize.map(Humanizer::new);
+        Ok(())
+    }
+
+    fn humanize<T>(&self, f: impl FnOnce(&mut Humanizer) -> T) -> Option<T> {
+        self.humanizer.lock().unwrap().as_mut().map(f)
+    }
+
+    pub fn get_mode(&self) -> Mode {
+        *self.mode.read().unwrap()
+    }
+
+    pub fn set_mode(&self, mode: Mode) {
+        *self.mode.write().unwrap() = mode;
+    }
+
+    /// 开启演练模式，之后的输入只记录到日志（可选同时写入 JSONL 文件）
+    pub fn start_dry_run(&self, path: Option<&Path>) -> Result<()> {
+        let event_log = EventLog::new(path)?;
+        *self.dry_run.lock().unwrap() = Some(event_log);
+        self.reset_target();
+        Ok(())
+    }
+
+    pub fn stop_dry_run(&self) {
+        *self.dry_run.lock().unwrap() = None;
+        self.reset_target();
+    }
+
+    // 之后的演练从系统鼠标位置重新开始
+    fn reset_target(&self) {
+        *self.target.lock().unwrap() = None;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.lock().unwrap().is_some()
+    }
+
+    pub fn get_input_log(&self) -> Vec<InputEvent> {
+        self.dry_run
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|event_log| event_log.events().to_vec())
+            .unwrap_or_default()
+    }
+
+    pub fn clear_input_log(&self) {
+        if let Some(event_log) = self.dry_run.lock().unwrap().as_mut() {
+            event_log.clear();
+        }
+    }
+
+    pub fn execute_token(&self, tokens: &Vec<Token>) -> Result<()> {
         for token in tokens.iter() {
-            self.send(token);
+            self.send(token)?;
+        }
+        Ok(())
+    }
+
+    fn click_button(&self, button: Button) -> Result<()> {
+        match self.humanize(|humanizer| humanizer.click_hold()) {
+            Some(hold) => {
+                self.send(&Token::Button(button, Press))?;
+                thread::sleep(hold);
+                self.send(&Token::Button(button, Release))
+            }
+            None => self.send(&Token::Button(button, Click)),
+        }
+    }
+
+    pub fn click_left(&self) -> Result<()> {
+        self.click_button(Button::Left)
+    }
+
+    pub fn click_right(&self) -> Result<()> {
+        self.click_button(Button::Right)
+    }
+
+    pub fn click_middle(&self) -> Result<()> {
+        self.click_button(Button::Middle)
+    }
+
+    /// 连续点击 `count` 次，用于双击、三击
+    pub fn click(&self, button: Button, count: u32, interval: Duration) -> Result<()> {
+        for index in 0..count {
+            if index > 0 {
+                thread::sleep(interval);
+            }
+            self.click_button(button)?;
         }
+        Ok(())
     }
 
-    pub fn click_left(&self) {
-        let token = Token::Button(Button::Left, Click);
-        self.send(&token);
+    pub fn double_click(&self, button: Button, interval: Duration) -> Result<()> {
+        self.click(button, 2, interval)
     }
 
-    pub fn click_right(&self) {
-        let token = Token::Button(Button::Right, Click);
-        self.send(&token);
+    pub fn click_at(
+        &self,
+        point: &Point,
+        button: Button,
+        count: u32,
+        interval: Duration,
+    ) -> Result<()> {
+        let point = self
+            .humanize(|humanizer| humanizer.click_point(point))
+            .unwrap_or(*point);
+        self.move_absolute(&point)?;
+        self.click(button, count, interval)
     }
 
-    pub fn press_left(&self) {
+    /// 在 `from` 按下按钮，用 `duration_millis` 拖到 `to` 后松开
+    ///
+    /// 两端都在允许区域内才按下；拖动途中出错时仍然松开按钮，返回第一个错误
+    pub fn drag(
+        &self,
+        from: &Point,
+        to: &Point,
+        button: Button,
+        duration_millis: u64,
+    ) -> Result<()> {
+        if let Some(region) = self.get_region() {
+            region.confine(from)?;
+            region.confine(to)?;
+        }
+        self.move_absolute(from)?;
+        self.send(&Token::Button(button, Press))?;
+        let moved = self.move_to(to, duration_millis, &Motion::default());
+        let released = self.send(&Token::Button(button, Release));
+        moved.and(released)
+    }
+
+    pub fn press_left(&self) -> Result<()> {
         let token = Token::Button(Button::Left, Press);
-        self.send(&token);
+        self.send(&token)
     }
 
-    pub fn press_right(&self) {
+    pub fn press_right(&self) -> Result<()> {
         let token = Token::Button(Button::Right, Press);
-        self.send(&token);
+        self.send(&token)
     }
 
-    pub fn release_left(&self) {
+    pub fn press_middle(&self) -> Result<()> {
+        let token = Token::Button(Button::Middle, Press);
+        self.send(&token)
+    }
+
+    pub fn release_left(&self) -> Result<()> {
         let token = Token::Button(Button::Left, Release);
-        self.send(&token);
+        self.send(&token)
     }
 
-    pub fn release_right(&self) {
+    pub fn release_right(&self) -> Result<()> {
         let token = Token::Button(Button::Right, Release);
-        self.send(&token);
+        self.send(&token)
+    }
+
+    pub fn release_middle(&self) -> Result<()> {
+        let token = Token::Button(Button::Middle, Release);
+        self.send(&token)
     }
 
-    pub fn move_absolute(&self, point: &Point) {
+    pub fn move_absolute(&self, point: &Point) -> Result<()> {
         let token = Token::MoveMouse(point.x, point.y, Abs);
-        self.send(&token);
+        self.send(&token)
+    }
+
+    /// 沿轨迹在 `duration_millis` 内移动到 `point`，而不是瞬间跳过去
+    pub fn move_to(&self, point: &Point, duration_millis: u64, motion: &Motion) -> Result<()> {
+        let from = self.get_mouse_location();
+        let steps = (duration_millis / MOVE_STEP_MILLIS).max(1);
+        let interval = Duration::from_millis(duration_millis / steps);
+        let path = motion.path(&from, point, steps as usize, &mut rand::thread_rng());
+        for point in path.iter() {
+            self.move_absolute(point)?;
+            thread::sleep(interval);
+        }
+        Ok(())
     }
 
-    pub fn move_relative(&self, point: &Point) {
+    pub fn move_relative(&self, point: &Point) -> Result<()> {
         let token = Token::MoveMouse(point.x, point.y, Rel);
-        self.send(&token);
+        self.send(&token)
     }
 
     pub fn get_mouse_location(&self) -> Point {
+        mouse_location()
+    }
+
+    pub fn get_mouse_buttons(&self) -> MouseButtons {
         let mouse = DEVICE_STATE.read().unwrap().get_mouse();
-        let location = mouse.coords;
-        Point::new(location.0, location.1)
+        MouseButtons::from_pressed(&mouse.button_pressed)
+    }
+
+    /// 当前按下的按键，无法映射的按键会被忽略
+    pub fn get_pressed_keys(&self) -> Vec<Key> {
+        let keys = DEVICE_STATE.read().unwrap().get_keys();
+        let mut pressed: Vec<Key> = Vec::new();
+        for key in keys
+            .into_iter()
+            .filter_map(|keycode| Key::try_from(keycode).ok())
+        {
+            if !pressed.contains(&key) {
+                pressed.push(key);
+            }
+        }
+        pressed
     }
 
-    pub fn scroll_vertical(&self, length: i32) {
+    /// 不区分左右的修饰键（例如 `ctrl`）按下任意一侧都返回 `true`
+    pub fn is_key_pressed(&self, key: impl AsRef<str>) -> Result<bool> {
+        let key = parse_key_name(key.as_ref())?;
+        Ok(self
+            .get_pressed_keys()
+            .iter()
+            .any(|pressed| *pressed == key || pressed.generic() == key))
+    }
+
+    pub fn get_lock_keys(&self) -> Result<LockKeys> {
+        LockKeys::current()
+    }
+
+    pub fn scroll_vertical(&self, length: i32) -> Result<()> {
         let token = Token::Scroll(length, Vertical);
-        self.send(&token);
+        self.send(&token)
     }
 
-    pub fn scroll_horizontal(&self, length: i32) {
+    pub fn scroll_horizontal(&self, length: i32) -> Result<()> {
         let token = Token::Scroll(length, Horizontal);
-        self.send(&token);
+        self.send(&token)
     }
 
     pub fn press_key(&self, key: impl AsRef<str>) -> Result<()> {
         let key = Key::from_str(key.as_ref())?;
         let token = Token::Key(key.into(), Press);
-        self.send(&token);
-        Ok(())
+        self.send(&token)
     }
 
     pub fn release_key(&self, key: impl AsRef<str>) -> Result<()> {
         let key = Key::from_str(key.as_ref())?;
         let token = Token::Key(key.into(), Release);
-        self.send(&token);
-        Ok(())
+        self.send(&token)
     }
 
     pub fn click_key(&self, key: impl AsRef<str>) -> Result<()> {
         let key = Key::from_str(key.as_ref())?;
         let token = Token::Key(key.into(), Click);
-        self.send(&token);
+        self.send(&token)
+    }
+
+    /// 执行组合键序列，例如 `"ctrl+shift+s"`、`"alt+tab, enter"`
+    ///
+    /// 修饰键按顺序按下，点击最后一个键后按相反顺序松开。
+    /// `hold` 为最后一个键按住的时长，`interval` 为两个组合键之间的间隔。
+    pub fn hotkey(
+        &self,
+        sequence: impl AsRef<str>,
+        hold: Duration,
+        interval: Duration,
+    ) -> Result<()> {
+        let chords = parse_hotkey(sequence)?;
+        let mode = self.get_mode();
+        for (index, chord) in chords.iter().enumerate() {
+            if index > 0 {
+                thread::sleep(interval);
+            }
+            self.send_chord(chord, hold, mode)?;
+        }
         Ok(())
     }
 
-    pub fn input_text(&self, text: impl AsRef<str>) {
-        let token = Token::Text(text.as_ref().to_string());
-        self.send(&token);
+    /// 执行一个组合键，中途出错时仍然按相反顺序松开已经按下的键，返回第一个错误
+    fn send_chord(&self, chord: &Chord, hold: Duration, mode: Mode) -> Result<()> {
+        let mut pressed = Vec::new();
+        let mut result = Ok(());
+        for modifier in chord.modifiers.iter() {
+            result = self.send_as(&Token::Key(*modifier, Press), mode);
+            if result.is_err() {
+                break;
+            }
+            pressed.push(*modifier);
+        }
+        if result.is_ok() {
+            result = if hold.is_zero() {
+                self.send_as(&Token::Key(chord.key, Click), mode)
+            } else {
+                self.send_as(&Token::Key(chord.key, Press), mode)
+                    .and_then(|_| {
+                        thread::sleep(hold);
+                        self.send_as(&Token::Key(chord.key, Release), mode)
+                    })
+            };
+        }
+        for modifier in pressed.iter().rev() {
+            let released = self.send_as(&Token::Key(*modifier, Release), mode);
+            if result.is_ok() {
+                result = released;
+            }
+        }
+        result
+    }
+
+    pub fn input_text(&self, text: impl AsRef<str>) -> Result<()> {
+        if self.get_humanize().is_none() {
+            let token = Token::Text(text.as_ref().to_string());
+            return self.send(&token);
+        }
+        for (index, char) in text.as_ref().chars().enumerate() {
+            if index > 0 {
+                let delay = self.humanize(|humanizer| humanizer.key_delay());
+                thread::sleep(delay.unwrap_or_default());
+            }
+            self.send(&Token::Text(char.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// 将文本写入剪贴板后发送粘贴快捷键，完成后恢复原来的剪贴板内容
+    ///
+    /// 粘贴总是同步执行，以免在目标程序读取剪贴板之前就恢复了剪贴板
+    pub fn paste_text(&self, text: impl AsRef<str>) -> Result<()> {
+        if self.is_dry_run() {
+            return self.input_text(text);
+        }
+        let previous = clipboard::snapshot();
+        clipboard::write_text(text.as_ref())?;
+        let result = self.paste();
+        thread::sleep(Duration::from_millis(PASTE_RESTORE_DELAY_MILLIS));
+        clipboard::restore(previous)?;
+        result
+    }
+
+    fn paste(&self) -> Result<()> {
+        for chord in parse_hotkey(paste_hotkey())? {
+            self.send_chord(&chord, Duration::ZERO, Mode::Sync)?;
+        }
+        Ok(())
+    }
+
+    pub fn input_text_with(&self, text: impl AsRef<str>, strategy: TextStrategy) -> Result<()> {
+        match strategy {
+            TextStrategy::Type => self.input_text(text),
+            TextStrategy::Paste => self.paste_text(text),
+        }
+    }
+}
+
+/// 输入线程执行的任务，`region` 存在时执行前检查坐标，`ack` 存在时执行完成后回传结果
+struct Job {
+    token: Token,
+    region: Option<Region>,
+    ack: Option<Sender<Result<()>>>,
+}
+
+/// 按允许区域检查坐标：移动被拒绝或收拢，鼠标在区域外时按下、点击和滚动被拒绝，松开始终允许
+///
+/// `location` 为执行输入时的鼠标位置，用户或其他脚本移动过鼠标也能检查出来
+fn confine(token: &Token, region: &Region, location: Point) -> Result<Token> {
+    match token {
+        Token::MoveMouse(x, y, Abs) => {
+            let point = region.confine(&Point::new(*x, *y))?;
+            Ok(Token::MoveMouse(point.x, point.y, Abs))
+        }
+        Token::MoveMouse(x, y, Rel) => {
+            let moved = Point::new(location.x + x, location.y + y);
+            let point = region.confine(&moved)?;
+            if point == moved {
+                Ok(token.clone())
+            } else {
+                Ok(Token::MoveMouse(point.x, point.y, Abs))
+            }
+        }
+        Token::Button(_, Press | Click) | Token::Scroll(..) => {
+            if region.contains(&location) {
+                Ok(token.clone())
+            } else {
+                Err(region.outside_error(&location))
+            }
+        }
+        _ => Ok(token.clone()),
+    }
+}
+
+fn mouse_location() -> Point {
+    let mouse = DEVICE_STATE.read().unwrap().get_mouse();
+    let location = mouse.coords;
+    Point::new(location.0, location.1)
+}
+
+/// 记录按下后尚未松开的按键和鼠标按钮
+fn track_held(token: &Token) {
+    let mut held = HELD_TOKENS.lock().unwrap();
+    match token {
+        Token::Key(key, direction) => {
+            held.retain(|t| !matches!(t, Token::Key(k, _) if k == key));
+            if *direction == Press {
+                held.push(token.clone());
+            }
+        }
+        Token::Button(button, direction) => {
+            held.retain(|t| !matches!(t, Token::Button(b, _) if b == button));
+            if *direction == Press {
+                held.push(token.clone());
+            }
+        }
+        _ => {}
     }
 }
 
 /// A temporary way to run enigo in sub-thread on macos
 /// https://github.com/enigo-rs/enigo/issues/436
-static INPUT_SENDER: LazyLock<Sender<Token>> = LazyLock::new(|| {
-    let (tx, rv) = unbounded::<Token>();
+static INPUT_CHANNEL: LazyLock<(Sender<Job>, Receiver<Job>)> = LazyLock::new(|| {
+    let (tx, rv) = unbounded::<Job>();
+    let receiver = rv.clone();
     thread::spawn(move || {
         let mut enigo = Enigo::new(&Settings::default()).unwrap();
-        while let Ok(token) = rv.recv() {
-            if let Err(error) = enigo.execute(&token) {
-                println!("{:?}", error);
+        while let Ok(job) = receiver.recv() {
+            let token = match job.region.as_ref() {
+                Some(region) => confine(&job.token, region, mouse_location()),
+                None => Ok(job.token),
+            };
+            let result = token.and_then(|token| {
+                enigo.execute(&token).map_err(|error| {
+                    anyhow!(t!("Failed to execute input.", error = error.to_string()))
+                })?;
+                track_held(&token);
+                Ok(())
+            });
+            match job.ack {
+                Some(ack) => {
+                    let _ = ack.send(result);
+                }
+                None => {
+                    if let Err(error) = result {
+                        Log::error(error.to_string()).send_to_app_log();
+                    }
+                }
             }
         }
     });
-    tx
+    (tx, rv)
 });
 
+static HELD_TOKENS: LazyLock<Mutex<Vec<Token>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
 static DEVICE_STATE: LazyLock<RwLock<DeviceState>> =
     LazyLock::new(|| RwLock::new(DeviceState::new()));
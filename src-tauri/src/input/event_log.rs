@@ -0,0 +1,59 @@
+use super::Token;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write as _},
+    path::Path,
+};
+
+/// 演练模式下记录的一次输入
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InputEvent {
+    pub time: DateTime<Local>,
+    pub token: Token,
+}
+
+/// 演练模式的输入日志，保存在内存中，并可同时追加写入 JSONL 文件
+pub struct EventLog {
+    events: Vec<InputEvent>,
+    writer: Option<BufWriter<File>>,
+}
+
+impl EventLog {
+    pub fn new(path: Option<&Path>) -> Result<Self> {
+        let writer = match path {
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                Some(BufWriter::new(file))
+            }
+            None => None,
+        };
+        Ok(Self {
+            events: Vec::new(),
+            writer,
+        })
+    }
+
+    pub fn record(&mut self, token: &Token) -> Result<()> {
+        let event = InputEvent {
+            time: Local::now(),
+            token: token.clone(),
+        };
+        if let Some(writer) = self.writer.as_mut() {
+            writeln!(writer, "{}", serde_json::to_string(&event)?)?;
+            writer.flush()?;
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
@@ -0,0 +1,105 @@
+use super::{Key, SystemKey};
+use anyhow::{Result, anyhow};
+use std::str::FromStr as _;
+
+/// 组合键之间的默认间隔（毫秒）
+pub const DEFAULT_HOTKEY_INTERVAL_MILLIS: u64 = 50;
+
+/// 一个组合键，例如 `ctrl+shift+s`
+///
+/// 除最后一个键以外都是修饰键，按顺序按下，最后一个键点击后再按相反顺序松开
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    pub modifiers: Vec<SystemKey>,
+    pub key: SystemKey,
+}
+
+impl Chord {
+    /// 转换为 [`parse_hotkey`] 可以解析的字符串，例如 `Control+C`
+    pub fn to_hotkey(&self) -> Result<String> {
+        let names = self
+            .modifiers
+            .iter()
+            .chain([&self.key])
+            .map(|key| Key::try_from(*key).map(|key| key.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(names.join("+"))
+    }
+}
+
+/// 解析组合键序列，组合键之间用 `,` 分隔，组合键内的按键用 `+` 连接
+///
+/// 例如：`"ctrl+shift+s"`、`"alt+tab, enter"`
+pub fn parse_hotkey(sequence: impl AsRef<str>) -> Result<Vec<Chord>> {
+    let sequence = sequence.as_ref();
+    let mut chords = Vec::new();
+    for chord in sequence.split(',') {
+        let names: Vec<&str> = chord.split('+').map(|name| name.trim()).collect();
+        if names.iter().any(|name| name.is_empty()) {
+            return Err(anyhow!(t!(
+                "The hotkey is invalid.",
+                hotkey = sequence.trim()
+            )));
+        }
+        let mut keys = names
+            .iter()
+            .map(|name| parse_key_name(name).map(SystemKey::from))
+            .collect::<Result<Vec<_>>>()?;
+        let key = keys.pop().unwrap();
+        chords.push(Chord {
+            modifiers: keys,
+            key,
+        });
+    }
+    Ok(chords)
+}
+
+/// 按键名称不区分大小写，并支持常用别名
+pub fn parse_key_name(name: &str) -> Result<Key> {
+    let lowercase = name.to_lowercase();
+    let alias = match lowercase.as_str() {
+        "ctrl" | "control" => Some("Control"),
+        "shift" => Some("Shift"),
+        "alt" | "option" => Some("Alt"),
+        "cmd" | "command" | "meta" | "win" | "super" => Some("Meta"),
+        "enter" | "return" => Some("Return"),
+        "esc" | "escape" => Some("Escape"),
+        "del" | "delete" => Some("Delete"),
+        "ins" | "insert" => Some("Insert"),
+        "up" => Some("UpArrow"),
+        "down" => Some("DownArrow"),
+        "left" => Some("LeftArrow"),
+        "right" => Some("RightArrow"),
+        "pgup" | "pageup" => Some("PageUp"),
+        "pgdn" | "pagedown" => Some("PageDown"),
+        "capslock" => Some("CapsLock"),
+        "numlock" => Some("NumLock"),
+        "scrolllock" => Some("ScrollLock"),
+        "printscreen" => Some("PrintScreen"),
+        "rctrl" | "rcontrol" => Some("ControlRight"),
+        "rshift" => Some("ShiftRight"),
+        "ralt" | "roption" | "altgr" => Some("AltRight"),
+        "rcmd" | "rmeta" | "rwin" => Some("MetaRight"),
+        _ => None,
+    };
+    if let Some(alias) = alias {
+        return Key::from_str(alias);
+    }
+    if let Ok(key) = Key::from_str(name) {
+        return Ok(key);
+    }
+    let candidate = match lowercase.chars().collect::<Vec<_>>().as_slice() {
+        [c] if c.is_ascii_digit() => format!("Num{}", c),
+        [c] => c.to_uppercase().to_string(),
+        [first, rest @ ..] => {
+            format!(
+                "{}{}",
+                first.to_uppercase(),
+                rest.iter().collect::<String>()
+            )
+        }
+        [] => String::new(),
+    };
+    Key::from_str(&candidate)
+        .map_err(|_| anyhow!(t!("The input key is not supported.", key = name)))
+}
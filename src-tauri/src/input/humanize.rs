@@ -0,0 +1,96 @@
+use crate::types::Point;
+use anyhow::{Result, anyhow};
+use rand::{Rng as _, SeedableRng as _, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 拟人化输入设置
+///
+/// * `click_hold_min_ms`/`click_hold_max_ms` - 点击时按住按钮的时长范围
+/// * `key_delay_min_ms`/`key_delay_max_ms` - 输入文本时两个字符之间的间隔范围，靠近中间值的概率更大
+/// * `click_offset` - 点击目标点周围的随机偏移半径（像素）
+/// * `seed` - 随机数种子，设置后每次运行的随机序列都相同
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Humanize {
+    pub click_hold_min_ms: u64,
+    pub click_hold_max_ms: u64,
+    pub key_delay_min_ms: u64,
+    pub key_delay_max_ms: u64,
+    pub click_offset: f64,
+    pub seed: Option<u64>,
+}
+
+impl Default for Humanize {
+    fn default() -> Self {
+        Self {
+            click_hold_min_ms: 40,
+            click_hold_max_ms: 120,
+            key_delay_min_ms: 30,
+            key_delay_max_ms: 150,
+            click_offset: 2.0,
+            seed: None,
+        }
+    }
+}
+
+impl Humanize {
+    pub fn validate(&self) -> Result<()> {
+        if self.click_hold_min_ms > self.click_hold_max_ms
+            || self.key_delay_min_ms > self.key_delay_max_ms
+            || self.click_offset.is_nan()
+            || self.click_offset < 0.0
+        {
+            return Err(anyhow!(t!("The humanize settings are invalid.")));
+        }
+        Ok(())
+    }
+}
+
+/// 按拟人化设置生成随机时长和偏移
+pub struct Humanizer {
+    settings: Humanize,
+    rng: StdRng,
+}
+
+impl Humanizer {
+    pub fn new(settings: Humanize) -> Self {
+        let rng = match settings.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self { settings, rng }
+    }
+
+    pub fn settings(&self) -> Humanize {
+        self.settings
+    }
+
+    pub fn click_hold(&mut self) -> Duration {
+        let millis = self
+            .rng
+            .gen_range(self.settings.click_hold_min_ms..=self.settings.click_hold_max_ms);
+        Duration::from_millis(millis)
+    }
+
+    // 两个均匀分布取平均得到三角分布，比均匀分布更接近人的节奏
+    pub fn key_delay(&mut self) -> Duration {
+        let range = self.settings.key_delay_min_ms..=self.settings.key_delay_max_ms;
+        let first = self.rng.gen_range(range.clone());
+        let second = self.rng.gen_range(range);
+        Duration::from_millis((first + second) / 2)
+    }
+
+    /// 在以 `point` 为圆心、`click_offset` 为半径的圆内随机取点
+    pub fn click_point(&mut self, point: &Point) -> Point {
+        if self.settings.click_offset <= 0.0 {
+            return *point;
+        }
+        let radius = self.settings.click_offset * self.rng.r#gen::<f64>().sqrt();
+        let angle = self.rng.gen_range(0.0..std::f64::consts::TAU);
+        Point::new(
+            point.x + (radius * angle.cos()).round() as i32,
+            point.y + (radius * angle.sin()).round() as i32,
+        )
+    }
+}
@@ -1,8 +1,10 @@
 // done
 use crate::input::SystemKey;
 use anyhow::{Error, Result, anyhow};
+use std::fmt::Display;
 use std::str::FromStr;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Key {
     Alt,
     Backspace,
@@ -110,6 +112,55 @@ pub enum Key {
     ScrollLock,
     Pause,
     NumLock,
+    /// 右侧的修饰键，`Alt`、`Control`、`Meta`、`Shift` 不区分左右
+    AltRight,
+    ControlRight,
+    MetaRight,
+    ShiftRight,
+}
+
+impl Key {
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self.generic(),
+            Key::Shift | Key::Control | Key::Alt | Key::Meta
+        )
+    }
+
+    /// 右侧的修饰键转换为不区分左右的按键，其他按键不变
+    pub fn generic(&self) -> Key {
+        match self {
+            Key::AltRight => Key::Alt,
+            Key::ControlRight => Key::Control,
+            Key::MetaRight => Key::Meta,
+            Key::ShiftRight => Key::Shift,
+            key => *key,
+        }
+    }
+
+    /// 不按修饰键时输入的字符，按美式键盘布局
+    pub fn to_char(&self) -> Option<char> {
+        let char = match self {
+            Key::Space => ' ',
+            Key::Minus => '-',
+            Key::Equal => '=',
+            Key::LeftBracket => '[',
+            Key::RightBracket => ']',
+            Key::BackSlash => '\\',
+            Key::SemiColon => ';',
+            Key::Quote => '\'',
+            Key::Comma => ',',
+            Key::Dot => '.',
+            Key::Slash => '/',
+            Key::BackQuote => '`',
+            key => match key.to_string().as_bytes() {
+                [letter] if letter.is_ascii_uppercase() => letter.to_ascii_lowercase() as char,
+                [b'N', b'u', b'm', digit] if digit.is_ascii_digit() => *digit as char,
+                _ => return None,
+            },
+        };
+        Some(char)
+    }
 }
 
 impl From<Key> for SystemKey {
@@ -584,6 +635,38 @@ impl From<Key> for SystemKey {
                 #[cfg(target_os = "windows")]
                 let key = SystemKey::Numlock;
                 key
+            }
+            Key::AltRight => {
+                #[cfg(target_os = "macos")]
+                let key = SystemKey::Other(61);
+
+                #[cfg(target_os = "windows")]
+                let key = SystemKey::RMenu;
+                key
+            }
+            Key::ControlRight => {
+                #[cfg(target_os = "macos")]
+                let key = SystemKey::Other(62);
+
+                #[cfg(target_os = "windows")]
+                let key = SystemKey::RControl;
+                key
+            }
+            Key::MetaRight => {
+                #[cfg(target_os = "macos")]
+                let key = SystemKey::Other(54);
+
+                #[cfg(target_os = "windows")]
+                let key = SystemKey::RWin;
+                key
+            }
+            Key::ShiftRight => {
+                #[cfg(target_os = "macos")]
+                let key = SystemKey::Other(60);
+
+                #[cfg(target_os = "windows")]
+                let key = SystemKey::RShift;
+                key
             } //SystemKey::Function
               // "AltGr" => SystemKey::Alt,
               // "ControlRight" => SystemKey::Control,
@@ -913,6 +996,26 @@ impl TryFrom<SystemKey> for Key {
             SystemKey::Other(71) => Key::NumLock,
             #[cfg(target_os = "windows")]
             SystemKey::Numlock => Key::NumLock,
+
+            #[cfg(target_os = "macos")]
+            SystemKey::Other(61) => Key::AltRight,
+            #[cfg(target_os = "windows")]
+            SystemKey::RMenu => Key::AltRight,
+
+            #[cfg(target_os = "macos")]
+            SystemKey::Other(62) => Key::ControlRight,
+            #[cfg(target_os = "windows")]
+            SystemKey::RControl => Key::ControlRight,
+
+            #[cfg(target_os = "macos")]
+            SystemKey::Other(54) => Key::MetaRight,
+            #[cfg(target_os = "windows")]
+            SystemKey::RWin => Key::MetaRight,
+
+            #[cfg(target_os = "macos")]
+            SystemKey::Other(60) => Key::ShiftRight,
+            #[cfg(target_os = "windows")]
+            SystemKey::RShift => Key::ShiftRight,
             _ => return Err(anyhow!(t!("The key is not supported."))),
         };
         Ok(key)
@@ -1002,6 +1105,7 @@ impl FromStr for Key {
             "V" => Key::V,
             "B" => Key::B,
             "N" => Key::N,
+            "M" => Key::M,
             "SemiColon" => Key::SemiColon,
             "Quote" => Key::Quote,
             "BackSlash" => Key::BackSlash,
@@ -1029,6 +1133,10 @@ impl FromStr for Key {
             "ScrollLock" => Key::ScrollLock,
             "Pause" => Key::Pause,
             "NumLock" => Key::NumLock,
+            "AltRight" => Key::AltRight,
+            "ControlRight" => Key::ControlRight,
+            "MetaRight" => Key::MetaRight,
+            "ShiftRight" => Key::ShiftRight,
             unknown_key => {
                 return Err(anyhow!(t!(
                     "The input key is not supported.",
@@ -1039,3 +1147,121 @@ impl FromStr for Key {
         Ok(key)
     }
 }
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Key::Alt => "Alt",
+            Key::Backspace => "Backspace",
+            Key::CapsLock => "CapsLock",
+            Key::Control => "Control",
+            Key::Delete => "Delete",
+            Key::DownArrow => "DownArrow",
+            Key::End => "End",
+            Key::Escape => "Escape",
+            Key::F1 => "F1",
+            Key::F2 => "F2",
+            Key::F3 => "F3",
+            Key::F4 => "F4",
+            Key::F5 => "F5",
+            Key::F6 => "F6",
+            Key::F7 => "F7",
+            Key::F8 => "F8",
+            Key::F9 => "F9",
+            Key::F10 => "F10",
+            Key::F11 => "F11",
+            Key::F12 => "F12",
+            Key::F13 => "F13",
+            Key::F14 => "F14",
+            Key::F15 => "F15",
+            Key::F16 => "F16",
+            Key::F17 => "F17",
+            Key::F18 => "F18",
+            Key::F19 => "F19",
+            Key::F20 => "F20",
+            Key::Home => "Home",
+            Key::LeftArrow => "LeftArrow",
+            Key::Meta => "Meta",
+            Key::PageDown => "PageDown",
+            Key::PageUp => "PageUp",
+            Key::Return => "Return",
+            Key::RightArrow => "RightArrow",
+            Key::Shift => "Shift",
+            Key::Space => "Space",
+            Key::Tab => "Tab",
+            Key::UpArrow => "UpArrow",
+            Key::LeftBracket => "LeftBracket",
+            Key::RightBracket => "RightBracket",
+            Key::Num1 => "Num1",
+            Key::Num2 => "Num2",
+            Key::Num3 => "Num3",
+            Key::Num4 => "Num4",
+            Key::Num5 => "Num5",
+            Key::Num6 => "Num6",
+            Key::Num7 => "Num7",
+            Key::Num8 => "Num8",
+            Key::Num9 => "Num9",
+            Key::Num0 => "Num0",
+            Key::Minus => "Minus",
+            Key::Equal => "Equal",
+            Key::Q => "Q",
+            Key::W => "W",
+            Key::E => "E",
+            Key::R => "R",
+            Key::T => "T",
+            Key::Y => "Y",
+            Key::U => "U",
+            Key::I => "I",
+            Key::O => "O",
+            Key::P => "P",
+            Key::A => "A",
+            Key::S => "S",
+            Key::D => "D",
+            Key::F => "F",
+            Key::G => "G",
+            Key::H => "H",
+            Key::J => "J",
+            Key::K => "K",
+            Key::L => "L",
+            Key::Z => "Z",
+            Key::X => "X",
+            Key::C => "C",
+            Key::V => "V",
+            Key::B => "B",
+            Key::N => "N",
+            Key::M => "M",
+            Key::SemiColon => "SemiColon",
+            Key::Quote => "Quote",
+            Key::BackSlash => "BackSlash",
+            Key::Comma => "Comma",
+            Key::Dot => "Dot",
+            Key::Slash => "Slash",
+            Key::KpMinus => "KpMinus",
+            Key::KpPlus => "KpPlus",
+            Key::KpMultiply => "KpMultiply",
+            Key::KpDivide => "KpDivide",
+            Key::Kp0 => "Kp0",
+            Key::Kp1 => "Kp1",
+            Key::Kp2 => "Kp2",
+            Key::Kp3 => "Kp3",
+            Key::Kp4 => "Kp4",
+            Key::Kp5 => "Kp5",
+            Key::Kp6 => "Kp6",
+            Key::Kp7 => "Kp7",
+            Key::Kp8 => "Kp8",
+            Key::Kp9 => "Kp9",
+            Key::KpDelete => "KpDelete",
+            Key::BackQuote => "BackQuote",
+            Key::Insert => "Insert",
+            Key::PrintScreen => "PrintScreen",
+            Key::ScrollLock => "ScrollLock",
+            Key::Pause => "Pause",
+            Key::NumLock => "NumLock",
+            Key::AltRight => "AltRight",
+            Key::ControlRight => "ControlRight",
+            Key::MetaRight => "MetaRight",
+            Key::ShiftRight => "ShiftRight",
+        };
+        write!(f, "{}", name)
+    }
+}
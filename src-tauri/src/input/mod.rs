@@ -1,6 +1,18 @@
+mod button;
+pub mod clipboard;
+mod device_key;
 mod engine;
+mod event_log;
+mod hotkey;
+mod humanize;
 mod key;
+mod mode;
+mod motion;
+mod region;
+mod state;
+mod text;
 
+pub use button::*;
 pub use engine::*;
 pub use enigo::agent::Token;
 pub use enigo::{
@@ -9,9 +21,18 @@ pub use enigo::{
     Coordinate::{Abs, Rel},
     Direction::{Click, Press, Release},
 };
+pub use event_log::InputEvent;
+pub use hotkey::*;
+pub use humanize::Humanize;
 
 /// 内部用的底层按键（KeyCode），不对前端暴露
 pub use enigo::Key as SystemKey;
 
 /// 业务层可用的按键类型（对前端暴露）
 pub use key::Key;
+
+pub use mode::Mode;
+pub use motion::{Curve, Motion};
+pub use region::{Region, RegionPolicy};
+pub use state::{LockKeys, MouseButtons};
+pub use text::TextStrategy;
@@ -0,0 +1,38 @@
+use anyhow::{Error, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 输入的执行方式
+///
+/// * `Sync` - 等待输入执行完成后返回，并返回执行时的错误
+/// * `Async` - 只把输入放入队列后立即返回，吞吐量更高，错误只写入日志
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    Sync,
+    Async,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Sync
+    }
+}
+
+impl FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mode = match s.to_lowercase().as_str() {
+            "sync" => Mode::Sync,
+            "async" => Mode::Async,
+            unknown_mode => {
+                return Err(anyhow!(t!(
+                    "The input mode is not supported.",
+                    mode = unknown_mode
+                )));
+            }
+        };
+        Ok(mode)
+    }
+}
@@ -0,0 +1,175 @@
+use crate::types::Point;
+use anyhow::{Error, Result, anyhow};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 两次移动之间的间隔（毫秒）
+pub const MOVE_STEP_MILLIS: u64 = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Curve {
+    Linear,
+    Eased,
+    Bezier,
+}
+
+impl FromStr for Curve {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let curve = match s.to_lowercase().as_str() {
+            "linear" => Curve::Linear,
+            "eased" => Curve::Eased,
+            "bezier" => Curve::Bezier,
+            unknown_curve => {
+                return Err(anyhow!(t!(
+                    "The movement curve is not supported.",
+                    curve = unknown_curve
+                )));
+            }
+        };
+        Ok(curve)
+    }
+}
+
+/// 鼠标移动轨迹
+///
+/// * `jitter` - 轨迹中间点的随机偏移（像素）
+/// * `overshoot` - 越过目标点的距离占总距离的比例，之后再修正回目标点
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Motion {
+    pub curve: Curve,
+    pub jitter: f64,
+    pub overshoot: f64,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self {
+            curve: Curve::Linear,
+            jitter: 0.0,
+            overshoot: 0.0,
+        }
+    }
+}
+
+impl Motion {
+    pub fn new(curve: Curve, jitter: f64, overshoot: f64) -> Self {
+        Self {
+            curve,
+            jitter: jitter.max(0.0),
+            overshoot: overshoot.max(0.0),
+        }
+    }
+
+    /// 生成从 `from` 到 `to` 的 `steps` 个轨迹点，最后一个点一定是 `to`
+    pub fn path(&self, from: &Point, to: &Point, steps: usize, rng: &mut impl Rng) -> Vec<Point> {
+        let steps = steps.max(1);
+        let start = (from.x as f64, from.y as f64);
+        let end = (to.x as f64, to.y as f64);
+
+        // 越过目标点时，大部分步数用来到达越界点，剩余步数用来修正
+        let (main_end, main_steps) = if self.overshoot > 0.0 && steps >= 4 {
+            let over = (
+                end.0 + (end.0 - start.0) * self.overshoot,
+                end.1 + (end.1 - start.1) * self.overshoot,
+            );
+            (over, steps - (steps / 5).max(1))
+        } else {
+            (end, steps)
+        };
+
+        let controls = match self.curve {
+            Curve::Bezier => Some(bezier_controls(start, main_end, &mut *rng)),
+            _ => None,
+        };
+
+        let mut points = Vec::with_capacity(steps);
+        for i in 1..=main_steps {
+            let t = i as f64 / main_steps as f64;
+            let (x, y) = match (self.curve, controls) {
+                (Curve::Bezier, Some((c1, c2))) => cubic_bezier(start, c1, c2, main_end, ease(t)),
+                (Curve::Eased, _) => lerp(start, main_end, ease(t)),
+                _ => lerp(start, main_end, t),
+            };
+            points.push((x, y));
+        }
+
+        let correction_steps = steps - main_steps;
+        for i in 1..=correction_steps {
+            let t = i as f64 / correction_steps as f64;
+            points.push(lerp(main_end, end, ease(t)));
+        }
+
+        let last = points.len() - 1;
+        points
+            .into_iter()
+            .enumerate()
+            .map(|(index, (x, y))| {
+                if index == last {
+                    return *to;
+                }
+                let (dx, dy) = if self.jitter > 0.0 {
+                    (
+                        rng.gen_range(-self.jitter..=self.jitter),
+                        rng.gen_range(-self.jitter..=self.jitter),
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+                Point::new((x + dx).round() as i32, (y + dy).round() as i32)
+            })
+            .collect()
+    }
+}
+
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+// ease-in-out cubic
+fn ease(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+fn cubic_bezier(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    p3: (f64, f64),
+    t: f64,
+) -> (f64, f64) {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    (
+        a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+        a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+    )
+}
+
+// 控制点沿垂直方向随机偏移，偏移量不超过距离的 30%
+fn bezier_controls(
+    start: (f64, f64),
+    end: (f64, f64),
+    rng: &mut impl Rng,
+) -> ((f64, f64), (f64, f64)) {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance < 1.0 {
+        return (start, end);
+    }
+    let (nx, ny) = (-dy / distance, dx / distance);
+    let spread = distance * 0.3;
+    let mut control = |t: f64| {
+        let offset = rng.gen_range(-spread..=spread);
+        let (x, y) = lerp(start, end, t);
+        (x + nx * offset, y + ny * offset)
+    };
+    (control(0.25), control(0.75))
+}
@@ -0,0 +1,82 @@
+use crate::types::Point;
+use anyhow::{Error, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 坐标超出允许区域时的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionPolicy {
+    Reject,
+    Clamp,
+}
+
+impl Default for RegionPolicy {
+    fn default() -> Self {
+        RegionPolicy::Reject
+    }
+}
+
+impl FromStr for RegionPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let policy = match s.to_lowercase().as_str() {
+            "reject" => RegionPolicy::Reject,
+            "clamp" => RegionPolicy::Clamp,
+            unknown_policy => {
+                return Err(anyhow!(t!(
+                    "The region policy is not supported.",
+                    policy = unknown_policy
+                )));
+            }
+        };
+        Ok(policy)
+    }
+}
+
+/// 允许输入的矩形区域（包含起点和终点）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub start_point: Point,
+    pub end_point: Point,
+    #[serde(default)]
+    pub policy: RegionPolicy,
+}
+
+impl Region {
+    pub fn new(start_point: Point, end_point: Point, policy: RegionPolicy) -> Self {
+        Self {
+            start_point,
+            end_point,
+            policy,
+        }
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.start_point.x..=self.end_point.x).contains(&point.x)
+            && (self.start_point.y..=self.end_point.y).contains(&point.y)
+    }
+
+    /// 区域内的坐标原样返回；区域外的坐标按策略拒绝或收拢到区域边缘
+    pub fn confine(&self, point: &Point) -> Result<Point> {
+        if self.contains(point) {
+            return Ok(*point);
+        }
+        match self.policy {
+            RegionPolicy::Reject => Err(self.outside_error(point)),
+            RegionPolicy::Clamp => Ok(Point::new(
+                point.x.clamp(self.start_point.x, self.end_point.x),
+                point.y.clamp(self.start_point.y, self.end_point.y),
+            )),
+        }
+    }
+
+    pub fn outside_error(&self, point: &Point) -> Error {
+        anyhow!(t!(
+            "The input position is outside the allowed region.",
+            x = point.x,
+            y = point.y
+        ))
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use anyhow::anyhow;
+use serde::Serialize;
+
+#[cfg(target_os = "macos")]
+use objc2::{class, msg_send};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetKeyState, VK_CAPITAL, VK_NUMLOCK, VK_SCROLL};
+
+/// 鼠标按键是否按下
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+pub struct MouseButtons {
+    pub left: bool,
+    pub right: bool,
+    pub middle: bool,
+}
+
+impl MouseButtons {
+    /// device_query 的按键状态从下标 1 开始：1 左键，2 右键，3 中键
+    pub fn from_pressed(pressed: &[bool]) -> Self {
+        let get = |index: usize| pressed.get(index).copied().unwrap_or(false);
+        Self {
+            left: get(1),
+            right: get(2),
+            middle: get(3),
+        }
+    }
+}
+
+/// 锁定键是否开启
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+pub struct LockKeys {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+    pub scroll_lock: bool,
+}
+
+impl LockKeys {
+    #[cfg(target_os = "windows")]
+    pub fn current() -> Result<Self> {
+        // 低位为 1 表示锁定键处于开启状态
+        let toggled = |key: i32| unsafe { GetKeyState(key) } & 1 != 0;
+        Ok(Self {
+            caps_lock: toggled(VK_CAPITAL.0 as i32),
+            num_lock: toggled(VK_NUMLOCK.0 as i32),
+            scroll_lock: toggled(VK_SCROLL.0 as i32),
+        })
+    }
+
+    /// macOS 没有数字锁定键和滚动锁定键
+    #[cfg(target_os = "macos")]
+    pub fn current() -> Result<Self> {
+        const NS_EVENT_MODIFIER_FLAG_CAPS_LOCK: usize = 1 << 16;
+        let flags: usize = unsafe { msg_send![class!(NSEvent), modifierFlags] };
+        Ok(Self {
+            caps_lock: flags & NS_EVENT_MODIFIER_FLAG_CAPS_LOCK != 0,
+            num_lock: false,
+            scroll_lock: false,
+        })
+    }
+
+    /// 无法读取时返回错误，避免脚本把未知当作关闭
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub fn current() -> Result<Self> {
+        Err(anyhow!(t!(
+            "Reading lock key states is not supported on this platform."
+        )))
+    }
+}
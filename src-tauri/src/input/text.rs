@@ -0,0 +1,45 @@
+use anyhow::{Error, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 粘贴后等待目标程序读取剪贴板的时长（毫秒），之后再恢复剪贴板
+pub const PASTE_RESTORE_DELAY_MILLIS: u64 = 100;
+
+/// 文本输入方式
+///
+/// * `Type` - 逐字符模拟键盘输入
+/// * `Paste` - 写入剪贴板后发送粘贴快捷键，适合长文本、输入法和非 BMP 字符
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextStrategy {
+    #[default]
+    Type,
+    Paste,
+}
+
+impl FromStr for TextStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let strategy = match s.to_lowercase().as_str() {
+            "type" => TextStrategy::Type,
+            "paste" => TextStrategy::Paste,
+            unknown_strategy => {
+                return Err(anyhow!(t!(
+                    "The text input strategy is not supported.",
+                    strategy = unknown_strategy
+                )));
+            }
+        };
+        Ok(strategy)
+    }
+}
+
+/// 当前平台的粘贴快捷键
+pub fn paste_hotkey() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "meta+v"
+    } else {
+        "ctrl+v"
+    }
+}
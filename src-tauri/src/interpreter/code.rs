@@ -1,5 +1,10 @@
+use crate::input::{Chord, Token};
 use crate::types::{ColoredPoint, HexColor, Point, RgbOffset};
-pub trait Code {
+use anyhow::Result;
+
+/// 生成各语言的脚本代码，录制脚本也通过它生成，新的语言实现它之后即可使用录制
+pub trait Code: Send {
+    /// 脚本开头的导入和客户端初始化，也用作录制脚本的开头
     fn predefined(&self) -> String;
 
     fn find_image(
@@ -36,4 +41,24 @@ pub trait Code {
     ) -> String;
 
     fn recognize_text(&self, start_point: &Point, end_point: &Point) -> String;
+
+    /// 移动到 `find_image` 找到的位置加上 `offset`，找不到时移动到 `fallback`
+    ///
+    /// 找到的位置和 `offset` 是截图中的像素，除以 `scale` 转换为鼠标坐标后再移动
+    fn move_to_found_image(
+        &self,
+        find_image: &str,
+        offset: &Point,
+        scale: (f64, f64),
+        fallback: &Point,
+    ) -> String;
+
+    fn sleep(&self, millis: u64) -> String;
+
+    fn comment(&self, text: &str) -> String;
+
+    fn hotkey(&self, chord: &Chord) -> Result<String>;
+
+    /// 录制到的单个输入，例如按键、鼠标移动
+    fn token(&self, token: &Token) -> Result<String>;
 }
@@ -1,29 +1,20 @@
 // done
-use super::Code;
+use super::javascript::{Engine as JavascriptEngine, PackageJson};
+use super::lua::{Engine as LuaEngine, get_kiwi_version as get_lua_kiwi_version};
 use super::python::{Engine as PythonEngine, PyProject};
+use super::rhai::{Engine as RhaiEngine, get_kiwi_version as get_rhai_kiwi_version};
+use super::{Code, RunOptions, ScriptExit};
 use crate::project::VerifyStatus;
-use crate::{
-    input::{SystemKey, Token},
-    interpreter::python::TokenExt as _,
-};
-use anyhow::{Error, Result, anyhow};
-use std::{
-    fmt::Display,
-    path::Path,
-    process::{ChildStderr, ChildStdout, ExitStatus},
-};
-use std::{
-    fs::File,
-    io::{BufWriter, Write as _},
-    sync::{Arc, Mutex},
-    time::Instant,
-};
+use anyhow::{Result, anyhow};
+use std::{fmt::Display, path::Path, time::Duration};
 
 #[derive(Clone)]
 pub enum Interpreter {
     Python(PythonEngine),
+    Javascript(JavascriptEngine),
+    Lua(LuaEngine),
+    Rhai(RhaiEngine),
     // Ruby(RubyEngine),
-    // Javascript(JavascriptEngine),
 }
 
 impl Interpreter {
@@ -35,6 +26,11 @@ impl Interpreter {
             "python" => Ok(Self::Python(PythonEngine::new_from_project_path(
                 project_path,
             ))),
+            "javascript" => Ok(Self::Javascript(JavascriptEngine::new_from_project_path(
+                project_path,
+            ))),
+            "lua" => Ok(Self::Lua(LuaEngine::new_from_project_path(project_path))),
+            "rhai" => Ok(Self::Rhai(RhaiEngine::new_from_project_path(project_path))),
             unknown_interpreter => Err(anyhow!(t!(
                 "The interpreter is not supported.",
                 interpreter = unknown_interpreter
@@ -48,54 +44,98 @@ impl Interpreter {
     ) -> VerifyStatus {
         match language.as_ref().to_lowercase().as_str() {
             "python" => PythonEngine::verify_in_project(project_path),
+            "javascript" => JavascriptEngine::verify_in_project(project_path),
+            "lua" => LuaEngine::verify_in_project(project_path),
+            "rhai" => RhaiEngine::verify_in_project(project_path),
             _ => VerifyStatus::Invalid,
         }
     }
 }
 
 impl Interpreter {
+    /// 创建同一项目的新实例，进程号和停止状态与原来的实例互不影响，用于同时运行多个脚本
+    pub fn new_instance(&self) -> Self {
+        match self {
+            Interpreter::Python(engine) => {
+                Interpreter::Python(PythonEngine::new_from_project_path(&engine.project_path))
+            }
+            Interpreter::Javascript(engine) => Interpreter::Javascript(
+                JavascriptEngine::new_from_project_path(&engine.project_path),
+            ),
+            Interpreter::Lua(engine) => {
+                Interpreter::Lua(LuaEngine::new_from_project_path(&engine.project_path))
+            }
+            Interpreter::Rhai(engine) => {
+                Interpreter::Rhai(RhaiEngine::new_from_project_path(&engine.project_path))
+            }
+        }
+    }
+
     pub fn init(&self) -> Result<()> {
         match self {
             Interpreter::Python(engine) => engine.init(),
+            Interpreter::Javascript(engine) => engine.init(),
+            Interpreter::Lua(engine) => engine.init(),
+            Interpreter::Rhai(engine) => engine.init(),
         }
     }
 
     pub fn reinit(&self) -> Result<()> {
         match self {
             Interpreter::Python(engine) => engine.reinit(),
+            Interpreter::Javascript(engine) => engine.reinit(),
+            Interpreter::Lua(engine) => engine.reinit(),
+            Interpreter::Rhai(engine) => engine.reinit(),
         }
     }
 
     pub fn run<
         OnSpawned: Fn(u32) + Send + 'static,
-        OnStdout: Fn(ChildStdout) + Send + 'static,
-        OnStderr: Fn(ChildStderr) + Send + 'static,
-        OnExit: Fn(u32, ExitStatus) + Send + 'static,
+        OnStdout: Fn(String) + Send + 'static,
+        OnStderr: Fn(String) + Send + 'static,
+        OnExit: Fn(u32, ScriptExit) + Send + 'static,
     >(
         &self,
         path: impl AsRef<Path>,
         port: u16,
+        options: &RunOptions,
         on_spawned: OnSpawned,
         on_stdout: OnStdout,
         on_stderr: OnStderr,
         on_exit: OnExit,
     ) -> Result<()> {
         match self {
-            Interpreter::Python(engine) => {
-                engine.run(path, port, on_spawned, on_stdout, on_stderr, on_exit)
-            }
+            Interpreter::Python(engine) => engine.run(
+                path, port, options, on_spawned, on_stdout, on_stderr, on_exit,
+            ),
+            Interpreter::Javascript(engine) => engine.run(
+                path, port, options, on_spawned, on_stdout, on_stderr, on_exit,
+            ),
+            Interpreter::Lua(engine) => engine.run(
+                path, port, options, on_spawned, on_stdout, on_stderr, on_exit,
+            ),
+            Interpreter::Rhai(engine) => engine.run(
+                path, port, options, on_spawned, on_stdout, on_stderr, on_exit,
+            ),
         }
     }
 
-    pub fn stop(&self) -> bool {
+    /// 停止脚本，外部解释器在 `grace` 内没有退出时强制结束
+    pub fn stop(&self, grace: Duration) -> bool {
         match self {
-            Interpreter::Python(engine) => engine.stop(),
+            Interpreter::Python(engine) => engine.stop(grace),
+            Interpreter::Javascript(engine) => engine.stop(grace),
+            Interpreter::Lua(engine) => engine.stop(grace),
+            Interpreter::Rhai(engine) => engine.stop(grace),
         }
     }
 
     pub fn get_pid(&self) -> u32 {
         match self {
             Interpreter::Python(engine) => engine.get_pid(),
+            Interpreter::Javascript(engine) => engine.get_pid(),
+            Interpreter::Lua(engine) => engine.get_pid(),
+            Interpreter::Rhai(engine) => engine.get_pid(),
         }
     }
 }
@@ -104,6 +144,9 @@ impl Display for Interpreter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
             Interpreter::Python(_) => "python",
+            Interpreter::Javascript(_) => "javascript",
+            Interpreter::Lua(_) => "lua",
+            Interpreter::Rhai(_) => "rhai",
         };
         write!(f, "{}", name)
     }
@@ -113,12 +156,18 @@ impl Interpreter {
     pub fn get_code(&self) -> Box<dyn Code> {
         match self {
             Interpreter::Python(engine) => Box::new(engine.code()),
+            Interpreter::Javascript(engine) => Box::new(engine.code()),
+            Interpreter::Lua(engine) => Box::new(engine.code()),
+            Interpreter::Rhai(engine) => Box::new(engine.code()),
         }
     }
 
     pub fn get_suffix(&self) -> String {
         match self {
             Interpreter::Python(_) => "py",
+            Interpreter::Javascript(_) => "js",
+            Interpreter::Lua(_) => "lua",
+            Interpreter::Rhai(_) => "rhai",
         }
         .to_string()
     }
@@ -126,6 +175,9 @@ impl Interpreter {
     pub fn get_main_file(&self) -> String {
         match self {
             Interpreter::Python(_) => "main.py",
+            Interpreter::Javascript(_) => "main.js",
+            Interpreter::Lua(_) => "main.lua",
+            Interpreter::Rhai(_) => "main.rhai",
         }
         .to_string()
     }
@@ -136,74 +188,21 @@ impl Interpreter {
                 let pyproject = PyProject::default();
                 pyproject.project.version
             }
+            Interpreter::Javascript(_) => PackageJson::kiwi().version,
+            Interpreter::Lua(_) => get_lua_kiwi_version(),
+            Interpreter::Rhai(_) => get_rhai_kiwi_version(),
         }
         .to_string()
     }
-
-    pub fn get_recorder_header_template(&self) -> String {
-        match self {
-            Interpreter::Python(_) => String::from(
-                "from kiwi import ScreenClient, System, Key, Point\n\
-             client = ScreenClient()",
-            ),
-        }
-    }
-
-    pub fn get_recorder_token_handler(
-        &self,
-        error_handler: Box<dyn Fn(Error) + Send + Sync + 'static>,
-    ) -> Box<dyn Fn(Arc<Mutex<Instant>>, Vec<Token>, Arc<Mutex<BufWriter<File>>>) + Send> {
-        match self {
-            Interpreter::Python(_) => {
-                let callback =
-                    move |last_call_time: Arc<Mutex<Instant>>,
-                          tokens: Vec<Token>,
-                          writer: Arc<Mutex<BufWriter<File>>>| {
-                        if tokens
-                            .iter()
-                            .any(|token| matches!(token, Token::Key(SystemKey::F12, _)))
-                        {
-                            return;
-                        }
-
-                        {
-                            let mut last_time = last_call_time.lock().unwrap();
-                            let now = Instant::now();
-                            let delta = now.duration_since(*last_time);
-                            let millis: u32 =
-                                (delta.as_secs() * 1000 + delta.subsec_millis() as u64) as u32;
-                            *last_time = now;
-                            let python_script = format!("System.sleep(milliseconds={})", millis);
-                            writeln!(writer.lock().unwrap(), "{}", &python_script).unwrap();
-                            writer.lock().unwrap().flush().unwrap();
-                        }
-                        {
-                            for token in tokens.iter() {
-                                match token.to_python_statement() {
-                                    Ok(python_script) => {
-                                        let full_python_script =
-                                            format!("client.{}", &python_script);
-                                        writeln!(writer.lock().unwrap(), "{}", &full_python_script)
-                                            .unwrap();
-                                        writer.lock().unwrap().flush().unwrap();
-                                    }
-                                    Err(error) => {
-                                        error_handler(error);
-                                    }
-                                }
-                            }
-                        }
-                    };
-                return Box::new(callback);
-            }
-        }
-    }
 }
 
 impl Into<String> for Interpreter {
     fn into(self) -> String {
         match self {
             Interpreter::Python(_) => "python",
+            Interpreter::Javascript(_) => "javascript",
+            Interpreter::Lua(_) => "lua",
+            Interpreter::Rhai(_) => "rhai",
         }
         .to_string()
     }
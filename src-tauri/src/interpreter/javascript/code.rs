@@ -0,0 +1,181 @@
+use super::TokenExt as _;
+use super::token_ext::javascript_string;
+use crate::input::{Chord, Token};
+use crate::interpreter::Code;
+use crate::types::{ColoredPoint, HexColor, Point, RgbOffset};
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct JavascriptCode {}
+
+impl Code for JavascriptCode {
+    fn predefined(&self) -> String {
+        format!(
+            r#"import {{ ScreenClient, Point, ColoredPoint, RgbOffset, System, Key }} from "kiwi";
+
+const client = new ScreenClient();
+"#
+        )
+    }
+
+    fn find_image(
+        &self,
+        subpath: &str,
+        start_point: &Point,
+        end_point: &Point,
+        threshold: f64,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let subpath = javascript_string(subpath);
+        format!(
+            r#"await client.findImage({{
+  subpath: {subpath},
+  startPoint: new Point({{ x: {sx}, y: {sy} }}),
+  endPoint: new Point({{ x: {ex}, y: {ey} }}),
+  threshold: {threshold},
+}})"#
+        )
+    }
+
+    fn find_images(
+        &self,
+        subpath: &str,
+        start_point: &Point,
+        end_point: &Point,
+        threshold: f64,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let subpath = javascript_string(subpath);
+        format!(
+            r#"await client.findImages({{
+  subpath: {subpath},
+  startPoint: new Point({{ x: {sx}, y: {sy} }}),
+  endPoint: new Point({{ x: {ex}, y: {ey} }}),
+  threshold: {threshold},
+}})"#
+        )
+    }
+
+    fn find_relative_colors(
+        &self,
+        vertex_hex: &str,
+        relative_points: &[ColoredPoint],
+        start_point: &Point,
+        end_point: &Point,
+        rgb_offset: &RgbOffset,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let RgbOffset { r, g, b } = rgb_offset;
+        let rows_str = relative_points
+            .iter()
+            .map(|p| {
+                let Point { x, y } = p.point;
+                let hex = p.clone().hex;
+                format!(
+                    "    new ColoredPoint({{ point: new Point({{ x: {x}, y: {y} }}), hex: \"{hex}\" }}),"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            r#"await client.findRelativeColors({{
+  vertexHex: "{vertex_hex}",
+  coloredPoints: [
+{rows_str}
+  ],
+  startPoint: new Point({{ x: {sx}, y: {sy} }}),
+  endPoint: new Point({{ x: {ex}, y: {ey} }}),
+  rgbOffset: new RgbOffset({{ r: {r}, g: {g}, b: {b} }}),
+}})"#
+        )
+    }
+
+    fn find_colors(
+        &self,
+        hex_colors: &[HexColor],
+        start_point: &Point,
+        end_point: &Point,
+        rgb_offset: &RgbOffset,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let RgbOffset { r, g, b } = rgb_offset;
+        let rows_str = hex_colors
+            .iter()
+            .map(|hex| format!("    \"{hex}\","))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            r#"await client.findColors({{
+  hexColors: [
+{rows_str}
+  ],
+  startPoint: new Point({{ x: {sx}, y: {sy} }}),
+  endPoint: new Point({{ x: {ex}, y: {ey} }}),
+  rgbOffset: new RgbOffset({{ r: {r}, g: {g}, b: {b} }}),
+}})"#
+        )
+    }
+
+    fn recognize_text(&self, start_point: &Point, end_point: &Point) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        format!(
+            r#"await client.recognizeText({{
+  startPoint: new Point({{ x: {sx}, y: {sy} }}),
+  endPoint: new Point({{ x: {ex}, y: {ey} }}),
+}})"#
+        )
+    }
+
+    // 放在代码块中，同一个脚本里多次使用时 `found` 不会重复声明
+    fn move_to_found_image(
+        &self,
+        find_image: &str,
+        offset: &Point,
+        scale: (f64, f64),
+        fallback: &Point,
+    ) -> String {
+        let Point { x: ox, y: oy } = offset;
+        let (sx, sy) = scale;
+        let Point { x: fx, y: fy } = fallback;
+        format!(
+            r#"{{
+  const found = ({find_image}).data;
+  await client.moveAbsolute({{
+    absolutePoint: found
+      ? new Point({{
+          x: Math.round((found.point.x + {ox}) / {sx:?}),
+          y: Math.round((found.point.y + {oy}) / {sy:?}),
+        }})
+      : new Point({{ x: {fx}, y: {fy} }}),
+  }});
+}}"#
+        )
+    }
+
+    fn sleep(&self, millis: u64) -> String {
+        format!("await System.sleep({{ milliseconds: {millis} }});")
+    }
+
+    fn comment(&self, text: &str) -> String {
+        format!("// {text}")
+    }
+
+    fn hotkey(&self, chord: &Chord) -> Result<String> {
+        Ok(format!(
+            "await client.hotkey({{ keys: \"{}\" }});",
+            chord.to_hotkey()?
+        ))
+    }
+
+    fn token(&self, token: &Token) -> Result<String> {
+        Ok(format!(
+            "await client.{};",
+            token.to_javascript_statement()?
+        ))
+    }
+}
@@ -0,0 +1,300 @@
+use super::{JavascriptCode, PackageJson};
+use crate::interpreter::{
+    RunOptions, ScriptExit, configure_process, forward_lines, terminate_tree, track_process,
+    wait_process,
+};
+use crate::{
+    app::App,
+    project::{Config, VerifyStatus},
+};
+use anyhow::{Result, anyhow};
+use fs_extra;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt as _;
+#[cfg(windows)]
+use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+/// 项目中保存 kiwi 包的目录，`package.json` 通过 `file:.kiwi/kiwi` 引用，
+/// 之后执行 `npm install` 时 kiwi 不会被当作多余的模块删除
+const KIWI_PACKAGE_DIR: &str = ".kiwi";
+
+#[derive(Clone)]
+pub struct Engine {
+    pub node: PathBuf,
+    pub project_path: PathBuf,
+    pub pid: Arc<AtomicU32>,
+}
+
+impl Engine {
+    pub fn new_from_project_path(project_path: impl AsRef<Path>) -> Self {
+        Self {
+            node: get_node(),
+            project_path: project_path.as_ref().to_path_buf(),
+            pid: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    pub fn verify_in_project(project_path: impl AsRef<Path>) -> VerifyStatus {
+        let project_path = PathBuf::from(project_path.as_ref());
+        // Check if main.js exists or VerifyStatus::Invalid
+        if !project_path.join("main.js").exists() {
+            return VerifyStatus::Invalid;
+        }
+        // Check if data/images exists or VerifyStatus::Invalid
+        if !project_path.join("data").join("images").exists() {
+            return VerifyStatus::Invalid;
+        }
+        // Check if kiwi package installed or VerifyStatus::Invalid
+        {
+            let path = project_path
+                .join("node_modules")
+                .join("kiwi")
+                .join("package.json");
+            let Ok(content) = fs::read_to_string(&path) else {
+                return VerifyStatus::Invalid;
+            };
+            let Ok(package) = PackageJson::load_from_json_content(&content) else {
+                return VerifyStatus::Invalid;
+            };
+            if package.name != "kiwi" {
+                return VerifyStatus::Invalid;
+            }
+        }
+        // Check if node is available or VerifyStatus::Invalid
+        {
+            let output = {
+                #[cfg(target_os = "macos")]
+                {
+                    Command::new(get_node()).arg("--version").output()
+                }
+                #[cfg(target_os = "windows")]
+                {
+                    Command::new(get_node())
+                        .arg("--version")
+                        .creation_flags(CREATE_NO_WINDOW.0)
+                        .output()
+                }
+            };
+            if !output.is_ok_and(|output| output.status.success()) {
+                return VerifyStatus::Invalid;
+            }
+        }
+        // node_modules 中没有绝对路径，项目移动后不需要重新初始化
+        VerifyStatus::Valid
+    }
+
+    pub fn reinit(&self) -> Result<()> {
+        let config = Config::new_from_toml(&self.project_path)?;
+        //delete
+        {
+            let need_to_removed = vec![self.project_path.join("node_modules")];
+            if let Err(e) = fs_extra::remove_items(&need_to_removed) {
+                return Err(anyhow!(t!(
+                    "Unable to delete the project’s Node.js modules.",
+                    error = e.to_string()
+                )));
+            }
+        }
+
+        // init
+        self.init()?;
+
+        // only desktop project needs to install dependencies in package.json.
+        // mobile project only can use what kiwi app provides.
+        if &config.project.kind != "desktop" {
+            return Ok(());
+        }
+
+        // npm may remove kiwi from node_modules, copy it again after installing.
+        if self.install_dependencies()? {
+            self.init()?;
+        }
+        Ok(())
+    }
+
+    pub fn init(&self) -> Result<()> {
+        //copy template to project, files edited by the user are kept.
+        {
+            let options = fs_extra::dir::CopyOptions::new()
+                .skip_exist(true)
+                .content_only(true);
+            let template_dir = App::get_resource_dir()
+                .join("javascript")
+                .join("project_template");
+            fs_extra::dir::copy(&template_dir, &self.project_path, &options)?;
+        }
+        fs::create_dir_all(self.project_path.join("data").join("images"))?;
+        // copy kiwi package, always overwrite to keep it the same version as the app
+        {
+            let kiwi_path = get_kiwi_package_path();
+            let options = fs_extra::dir::CopyOptions::new().overwrite(true);
+            for dir in [
+                self.project_path.join(KIWI_PACKAGE_DIR),
+                self.project_path.join("node_modules"),
+            ] {
+                fs::create_dir_all(&dir)?;
+                if let Err(e) = fs_extra::dir::copy(&kiwi_path, &dir, &options) {
+                    return Err(anyhow!(t!(
+                        "Failed to install the kiwi package.",
+                        error = e.to_string()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // install the dependencies in package.json, returns false if there is nothing to install except kiwi.
+    fn install_dependencies(&self) -> Result<bool> {
+        let package_path = self.project_path.join("package.json");
+        let Ok(content) = fs::read_to_string(&package_path) else {
+            return Ok(false);
+        };
+        let package = PackageJson::load_from_json_content(&content)?;
+        if package.dependencies.keys().all(|name| name == "kiwi") {
+            return Ok(false);
+        }
+        let output = {
+            #[cfg(target_os = "macos")]
+            {
+                Command::new(get_npm())
+                    .arg("install")
+                    .current_dir(&self.project_path)
+                    .output()
+            }
+            #[cfg(target_os = "windows")]
+            {
+                Command::new(get_npm())
+                    .arg("install")
+                    .current_dir(&self.project_path)
+                    .creation_flags(CREATE_NO_WINDOW.0)
+                    .output()
+            }
+        }?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!(t!(
+                "Failed to install the Node.js module.",
+                error = stderr.to_string()
+            )));
+        }
+        Ok(true)
+    }
+
+    pub fn run<
+        OnSpawned: Fn(u32) + Send + 'static,
+        OnStdout: Fn(String) + Send + 'static,
+        OnStderr: Fn(String) + Send + 'static,
+        OnExit: Fn(u32, ScriptExit) + Send + 'static,
+    >(
+        &self,
+        path: impl AsRef<Path>,
+        port: u16,
+        options: &RunOptions,
+        on_spawned: OnSpawned,
+        on_stdout: OnStdout,
+        on_stderr: OnStderr,
+        on_exit: OnExit,
+    ) -> Result<()> {
+        let mut command = Command::new(&self.node);
+        command
+            .arg(path.as_ref())
+            .arg("--port")
+            .arg(port.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        options.apply(&mut command, &self.project_path);
+        configure_process(&mut command);
+        let mut child = command.spawn().map_err(|e| {
+            anyhow!(t!(
+                "Node.js not found.",
+                path = self.node.to_string_lossy(),
+                error = e.to_string()
+            ))
+        })?;
+        track_process(&child);
+        let pid = child.id();
+        self.pid.store(pid, Ordering::SeqCst);
+        on_spawned(pid);
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, on_stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, on_stderr);
+        }
+        let exit_status = wait_process(&mut child)?;
+        on_exit(pid, ScriptExit::Process(exit_status));
+        self.pid.store(0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// 先请求脚本退出，`grace` 后仍未退出时强制结束脚本及其子进程，不等待进程结束
+    pub fn stop(&self, grace: Duration) -> bool {
+        let pid = self.pid.load(Ordering::SeqCst);
+        if pid == 0 {
+            return false;
+        }
+        let current = Arc::clone(&self.pid);
+        terminate_tree(pid, grace, move || current.load(Ordering::SeqCst) != pid);
+        true
+    }
+
+    pub fn get_pid(&self) -> u32 {
+        self.pid.load(Ordering::SeqCst)
+    }
+}
+
+impl Engine {
+    pub fn code(&self) -> JavascriptCode {
+        JavascriptCode::default()
+    }
+}
+
+fn get_node_dir() -> PathBuf {
+    App::get_resource_dir().join("javascript").join("node")
+}
+
+/// 优先使用随应用分发的 Node.js，没有时使用 PATH 中的 `node`
+pub fn get_node() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    let bundled = get_node_dir().join("bin").join("node");
+
+    #[cfg(target_os = "windows")]
+    let bundled = get_node_dir().join("node.exe");
+
+    if bundled.exists() {
+        return bundled;
+    }
+    PathBuf::from("node")
+}
+
+fn get_npm() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    let (bundled, fallback) = (get_node_dir().join("bin").join("npm"), "npm");
+
+    #[cfg(target_os = "windows")]
+    let (bundled, fallback) = (get_node_dir().join("npm.cmd"), "npm.cmd");
+
+    if bundled.exists() {
+        return bundled;
+    }
+    PathBuf::from(fallback)
+}
+
+fn get_kiwi_package_path() -> PathBuf {
+    App::get_resource_dir()
+        .join("javascript")
+        .join("packages")
+        .join("kiwi")
+}
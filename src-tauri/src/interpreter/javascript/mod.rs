@@ -0,0 +1,10 @@
+mod code;
+mod engine;
+mod package;
+mod token_ext;
+
+pub use engine::*;
+pub use package::PackageJson;
+pub use token_ext::*;
+
+use code::*;
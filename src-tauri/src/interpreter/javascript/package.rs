@@ -0,0 +1,35 @@
+use crate::{extensions::EmbeddedFileExt as _, types::Asset};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+/// `package.json` 中用到的字段
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Default)]
+pub struct PackageJson {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl PackageJson {
+    /// 内置的 kiwi 包
+    pub fn kiwi() -> Self {
+        let json = Asset::get("javascript/packages/kiwi/package.json")
+            .expect("get kiwi package.json failed.");
+        let mut json_content = String::new();
+        json.to_cursor()
+            .read_to_string(&mut json_content)
+            .expect("read kiwi package.json failed");
+        PackageJson::load_from_json_content(&json_content).expect("load kiwi package.json failed.")
+    }
+
+    pub fn load_from_json_content(json_content: &str) -> Result<PackageJson> {
+        let package: PackageJson = serde_json::from_str(json_content)?;
+        Ok(package)
+    }
+}
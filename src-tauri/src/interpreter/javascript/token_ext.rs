@@ -0,0 +1,58 @@
+use crate::input::{Abs, Button, Click, Horizontal, Key, Press, Rel, Release, Token, Vertical};
+use anyhow::{Result, anyhow};
+
+pub trait TokenExt {
+    fn to_javascript_statement(&self) -> Result<String>;
+}
+
+impl TokenExt for Token {
+    fn to_javascript_statement(&self) -> Result<String> {
+        let javascript_script = match self {
+            Token::Text(text) => format!("inputText({{ text: {} }})", javascript_string(text)),
+            Token::Key(system_key, direction) => {
+                let key: Key = system_key.to_owned().try_into()?;
+                let key_statement = format!("Key.{}", key);
+                match direction {
+                    Press => format!("pressKey({{ key: {} }})", key_statement),
+                    Release => format!("releaseKey({{ key: {} }})", key_statement),
+                    Click => format!("clickKey({{ key: {} }})", key_statement),
+                }
+            }
+            Token::Button(button, direction) => match (button, direction) {
+                (Button::Left, Press) => format!("pressLeft()"),
+                (Button::Left, Release) => format!("releaseLeft()"),
+                (Button::Left, Click) => format!("clickLeft()"),
+                (Button::Right, Press) => format!("pressRight()"),
+                (Button::Right, Release) => format!("releaseRight()"),
+                (Button::Right, Click) => format!("clickRight()"),
+                (Button::Middle, Press) => format!("pressMiddle()"),
+                (Button::Middle, Release) => format!("releaseMiddle()"),
+                (Button::Middle, Click) => format!("clickMiddle()"),
+                (_, _) => {
+                    return Err(anyhow!(t!("Unsupported button action.")));
+                }
+            },
+            Token::MoveMouse(x, y, coordinate) => match coordinate {
+                Abs => format!(
+                    "moveAbsolute({{ absolutePoint: new Point({{ x: {}, y: {} }}) }})",
+                    x, y
+                ),
+                Rel => format!(
+                    "moveRelative({{ offset: new Point({{ x: {}, y: {} }}) }})",
+                    x, y
+                ),
+            },
+            Token::Scroll(length, axis) => match axis {
+                Horizontal => format!("scrollHorizontal({{ length: {} }})", length),
+                Vertical => format!("scrollVertical({{ length: {} }})", length),
+            },
+            _ => return Err(anyhow!(t!("Unsupported token."))),
+        };
+        Ok(javascript_script)
+    }
+}
+
+// 转换为 JavaScript 字符串字面量
+pub(super) fn javascript_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| String::from("\"\""))
+}
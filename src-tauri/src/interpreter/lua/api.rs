@@ -0,0 +1,195 @@
+use crate::input::Key;
+use crate::interpreter::RunOptions;
+use crate::run::RunId;
+use crate::websocket;
+use mlua::{
+    Error as LuaError, Function, Lua, LuaSerdeExt as _, SerializeOptions, Table, Value, Variadic,
+};
+use std::{
+    env,
+    path::Path,
+    str::FromStr as _,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// 等待时检查是否停止的间隔
+const SLEEP_SLICE_MILLIS: u64 = 10;
+
+/// 替换可以捕获错误的函数，停止后调用前和返回后再次抛出停止的错误，脚本不能捕获停止后继续运行。
+/// 用 Lua 编写，被替换的函数仍然可以在协程中让出
+const GUARD_PROTECTED_CALLS: &str = r#"
+local check = ...
+local function checked(...)
+  check()
+  return ...
+end
+local function guard(protected)
+  return function(...)
+    check()
+    return checked(protected(...))
+  end
+end
+pcall = guard(pcall)
+xpcall = guard(xpcall)
+coroutine.resume = guard(coroutine.resume)
+"#;
+
+/// 注册 `kiwi` 模块，脚本通过 `require("kiwi")` 使用
+///
+/// * `client` - 与 websocket 客户端的方法同名，参数为同名字段组成的表，直接调用 Kiwi 内部的处理函数，
+///   返回值为原来响应中的 `data`，出错时抛出 Lua 错误
+/// * `System.sleep` - 等待期间可以被停止
+/// * `Key` - 按键名称，例如 `Key.Return`
+///
+/// 同时将 `print` 输出到 Kiwi 的日志，移除会结束整个 Kiwi 进程的 `os.exit`，
+/// 并让 `pcall`、`xpcall` 和 `coroutine.resume` 在停止后不能捕获停止的错误。
+/// 这里创建的函数都不持有 Lua 的值，脚本结束后 Lua 可以被完整释放。
+pub(super) fn register(
+    lua: &Lua,
+    project_path: &Path,
+    run_id: Option<RunId>,
+    cancelled: Arc<AtomicBool>,
+    on_stdout: impl Fn(String) + 'static,
+) -> mlua::Result<()> {
+    let kiwi = lua.create_table()?;
+    kiwi.set(
+        "client",
+        create_client(lua, run_id, Arc::clone(&cancelled))?,
+    )?;
+    kiwi.set("System", create_system(lua, Arc::clone(&cancelled))?)?;
+    kiwi.set("Key", create_key(lua)?)?;
+
+    let globals = lua.globals();
+    let package: Table = globals.get("package")?;
+    package.get::<Table>("loaded")?.set("kiwi", kiwi)?;
+    // 可以 require 项目中的其他 Lua 文件
+    let project_path = project_path.to_string_lossy();
+    let path: String = package.get("path")?;
+    package.set(
+        "path",
+        format!("{project_path}/?.lua;{project_path}/?/init.lua;{path}"),
+    )?;
+
+    globals.set("print", create_print(lua, on_stdout)?)?;
+    globals.get::<Table>("os")?.set("exit", Value::Nil)?;
+    let check = lua.create_function(move |_, ()| check_cancelled(&cancelled))?;
+    lua.load(GUARD_PROTECTED_CALLS)
+        .set_name("=kiwi")
+        .call::<()>(check)
+}
+
+/// 设置脚本的参数和环境变量
+///
+/// * `arg` - 与独立的 Lua 解释器相同，`arg[0]` 为脚本路径，`arg[1]` 开始为参数
+/// * `os.getenv` - 优先返回运行选项中的环境变量，Kiwi 进程的环境变量不会被修改
+pub(super) fn register_run_options(
+    lua: &Lua,
+    path: &Path,
+    options: &RunOptions,
+) -> mlua::Result<()> {
+    let arg = lua.create_table()?;
+    arg.raw_set(0, path.to_string_lossy().to_string())?;
+    for (index, value) in options.args.iter().enumerate() {
+        arg.raw_set(index + 1, value.as_str())?;
+    }
+    lua.globals().set("arg", arg)?;
+
+    let run_env = options.env.clone();
+    let getenv = lua.create_function(move |_, name: String| {
+        Ok(run_env.get(&name).cloned().or_else(|| env::var(&name).ok()))
+    })?;
+    lua.globals().get::<Table>("os")?.set("getenv", getenv)?;
+    Ok(())
+}
+
+pub(super) fn check_cancelled(cancelled: &AtomicBool) -> mlua::Result<()> {
+    if cancelled.load(Ordering::SeqCst) {
+        return Err(LuaError::runtime(t!("The script has been stopped.")));
+    }
+    Ok(())
+}
+
+// 第一次访问某个方法时创建对应的函数并缓存在表中
+fn create_client(
+    lua: &Lua,
+    run_id: Option<RunId>,
+    cancelled: Arc<AtomicBool>,
+) -> mlua::Result<Table> {
+    let client = lua.create_table()?;
+    let metatable = lua.create_table()?;
+    let index = lua.create_function(move |lua, (client, method): (Table, String)| {
+        let Some(handler) = websocket::get_method_handler(&method) else {
+            return Ok(Value::Nil);
+        };
+        let cancelled = Arc::clone(&cancelled);
+        let function = lua.create_function(move |lua, args: Option<Value>| {
+            check_cancelled(&cancelled)?;
+            if let Some(run_id) = run_id {
+                websocket::record_call(run_id);
+            }
+            let args = match args {
+                Some(args) => lua.from_value(args)?,
+                None => serde_json::Value::Null,
+            };
+            let data = handler(args).map_err(|error| LuaError::runtime(error.to_string()))?;
+            // null 转换为 nil，脚本中可以直接判断
+            let options = SerializeOptions::new()
+                .serialize_none_to_null(false)
+                .serialize_unit_to_null(false);
+            lua.to_value_with(&data, options)
+        })?;
+        client.raw_set(method, function.clone())?;
+        Ok(Value::Function(function))
+    })?;
+    metatable.set("__index", index)?;
+    client.set_metatable(Some(metatable));
+    Ok(client)
+}
+
+fn create_system(lua: &Lua, cancelled: Arc<AtomicBool>) -> mlua::Result<Table> {
+    let system = lua.create_table()?;
+    let sleep = lua.create_function(move |_, options: Table| {
+        let milliseconds: i64 = options.get("milliseconds")?;
+        let deadline = Instant::now() + Duration::from_millis(milliseconds.max(0) as u64);
+        loop {
+            check_cancelled(&cancelled)?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            thread::sleep(remaining.min(Duration::from_millis(SLEEP_SLICE_MILLIS)));
+        }
+    })?;
+    system.set("sleep", sleep)?;
+    Ok(system)
+}
+
+fn create_key(lua: &Lua) -> mlua::Result<Table> {
+    let key = lua.create_table()?;
+    let metatable = lua.create_table()?;
+    let index = lua.create_function(|_, (_, name): (Table, String)| {
+        Key::from_str(&name)
+            .map(|key| key.to_string())
+            .map_err(|error| LuaError::runtime(error.to_string()))
+    })?;
+    metatable.set("__index", index)?;
+    key.set_metatable(Some(metatable));
+    Ok(key)
+}
+
+fn create_print(lua: &Lua, on_stdout: impl Fn(String) + 'static) -> mlua::Result<Function> {
+    lua.create_function(move |lua, values: Variadic<Value>| {
+        let tostring: Function = lua.globals().get("tostring")?;
+        let values = values
+            .into_iter()
+            .map(|value| tostring.call::<String>(value))
+            .collect::<mlua::Result<Vec<_>>>()?;
+        on_stdout(values.join("\t"));
+        Ok(())
+    })
+}
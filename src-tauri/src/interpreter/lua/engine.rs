@@ -0,0 +1,175 @@
+use super::{LuaCode, api};
+use crate::{
+    app::App,
+    interpreter::{RunOptions, ScriptExit},
+    project::VerifyStatus,
+};
+use anyhow::{Result, anyhow};
+use fs_extra;
+use mlua::{HookTriggers, Lua, VmState};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+/// 每执行这么多条指令检查一次是否停止
+const CANCEL_CHECK_INSTRUCTIONS: u32 = 1000;
+
+/// 在 Kiwi 进程内运行 Lua 脚本
+///
+/// 脚本直接调用截图、找图、OCR 和输入的处理函数，不需要虚拟环境和 websocket。
+/// 停止时设置取消标记，脚本在执行下一批指令、调用 Kiwi 的方法或等待时结束，
+/// 之后每次检查都会再次抛出停止的错误，`pcall` 捕获后脚本也不能继续运行。
+#[derive(Clone)]
+pub struct Engine {
+    pub project_path: PathBuf,
+    running: Arc<AtomicBool>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Engine {
+    pub fn new_from_project_path(project_path: impl AsRef<Path>) -> Self {
+        Self {
+            project_path: project_path.as_ref().to_path_buf(),
+            running: Arc::new(AtomicBool::new(false)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn verify_in_project(project_path: impl AsRef<Path>) -> VerifyStatus {
+        let project_path = project_path.as_ref();
+        // Check if main.lua exists or VerifyStatus::Invalid
+        if !project_path.join("main.lua").exists() {
+            return VerifyStatus::Invalid;
+        }
+        // Check if data/images exists or VerifyStatus::Invalid
+        if !project_path.join("data").join("images").exists() {
+            return VerifyStatus::Invalid;
+        }
+        VerifyStatus::Valid
+    }
+
+    // nothing is installed into the project, init again to restore missing files.
+    pub fn reinit(&self) -> Result<()> {
+        self.init()
+    }
+
+    pub fn init(&self) -> Result<()> {
+        //copy template to project, files edited by the user are kept.
+        {
+            let options = fs_extra::dir::CopyOptions::new()
+                .skip_exist(true)
+                .content_only(true);
+            let template_dir = App::get_resource_dir().join("lua").join("project_template");
+            fs_extra::dir::copy(&template_dir, &self.project_path, &options)?;
+        }
+        fs::create_dir_all(self.project_path.join("data").join("images"))?;
+        Ok(())
+    }
+
+    /// 脚本在当前线程中执行，`port` 不会用到；运行期间以 Kiwi 的进程号作为 pid
+    pub fn run<
+        OnSpawned: Fn(u32) + Send + 'static,
+        OnStdout: Fn(String) + Send + 'static,
+        OnStderr: Fn(String) + Send + 'static,
+        OnExit: Fn(u32, ScriptExit) + Send + 'static,
+    >(
+        &self,
+        path: impl AsRef<Path>,
+        _port: u16,
+        options: &RunOptions,
+        on_spawned: OnSpawned,
+        on_stdout: OnStdout,
+        on_stderr: OnStderr,
+        on_exit: OnExit,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| {
+            anyhow!(t!(
+                "Unable to read the script.",
+                path = path.to_string_lossy(),
+                error = e.to_string()
+            ))
+        })?;
+        self.cancelled.store(false, Ordering::SeqCst);
+        self.running.store(true, Ordering::SeqCst);
+        let pid = std::process::id();
+        on_spawned(pid);
+        let exit = match self.execute(path, &source, options, on_stdout) {
+            Ok(()) => ScriptExit::Finished,
+            Err(_) if self.cancelled.load(Ordering::SeqCst) => ScriptExit::Stopped,
+            Err(error) => {
+                for line in error.to_string().lines() {
+                    on_stderr(line.to_string());
+                }
+                ScriptExit::Failed
+            }
+        };
+        self.running.store(false, Ordering::SeqCst);
+        on_exit(pid, exit);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        path: &Path,
+        source: &str,
+        options: &RunOptions,
+        on_stdout: impl Fn(String) + 'static,
+    ) -> mlua::Result<()> {
+        let lua = Lua::new();
+        api::register(
+            &lua,
+            &self.project_path,
+            options.run_id,
+            Arc::clone(&self.cancelled),
+            on_stdout,
+        )?;
+        api::register_run_options(&lua, path, options)?;
+        let cancelled = Arc::clone(&self.cancelled);
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(CANCEL_CHECK_INSTRUCTIONS),
+            move |_, _| {
+                api::check_cancelled(&cancelled)?;
+                Ok(VmState::Continue)
+            },
+        );
+        let name = path.strip_prefix(&self.project_path).unwrap_or(path);
+        lua.load(source)
+            .set_name(format!("@{}", name.to_string_lossy()))
+            .exec()
+    }
+
+    /// 脚本在 Kiwi 进程内运行，不能强制结束，`grace` 不会用到
+    pub fn stop(&self, _grace: Duration) -> bool {
+        if !self.running.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.cancelled.store(true, Ordering::SeqCst);
+        true
+    }
+
+    pub fn get_pid(&self) -> u32 {
+        if self.running.load(Ordering::SeqCst) {
+            std::process::id()
+        } else {
+            0
+        }
+    }
+}
+
+impl Engine {
+    pub fn code(&self) -> LuaCode {
+        LuaCode::default()
+    }
+}
+
+/// 脚本使用的是当前 Kiwi 提供的方法，版本与 Kiwi 相同
+pub fn get_kiwi_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
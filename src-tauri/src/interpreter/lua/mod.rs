@@ -0,0 +1,9 @@
+mod api;
+mod code;
+mod engine;
+mod token_ext;
+
+pub use engine::*;
+pub use token_ext::*;
+
+use code::*;
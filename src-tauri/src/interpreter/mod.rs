@@ -1,6 +1,14 @@
 mod code;
 mod interpreter;
+pub mod javascript;
+pub mod lua;
+mod process;
 pub mod python;
+pub mod rhai;
+mod run;
 
-use code::*;
+pub use code::*;
 pub use interpreter::*;
+pub use run::*;
+
+pub(crate) use process::*;
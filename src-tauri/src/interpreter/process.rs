@@ -0,0 +1,308 @@
+use std::{
+    io,
+    process::{Child, Command, ExitStatus},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(windows)]
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    os::windows::{io::AsRawHandle as _, process::CommandExt as _},
+    sync::{LazyLock, Mutex},
+};
+#[cfg(target_os = "macos")]
+use std::{
+    collections::HashMap,
+    sync::{Condvar, LazyLock, Mutex},
+};
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{CloseHandle, HANDLE},
+    System::{
+        Console::{
+            ATTACH_PARENT_PROCESS, AttachConsole, CTRL_BREAK_EVENT, FreeConsole,
+            GenerateConsoleCtrlEvent, SetConsoleCtrlHandler,
+        },
+        JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, TerminateJobObject,
+        },
+        Threading::{CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW},
+    },
+};
+
+/// 等待脚本进程退出时的检查间隔
+const EXIT_POLL_MILLIS: u64 = 50;
+
+/// 设置脚本进程，停止时可以结束脚本启动的子进程
+///
+/// macOS 上脚本进程成为新进程组的组长；Windows 上不显示控制台窗口，
+/// 并成为新进程组的组长，可以单独收到 `CTRL_BREAK_EVENT`
+pub(crate) fn configure_process(command: &mut Command) {
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::process::CommandExt as _;
+        command.process_group(0);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        command.creation_flags(CREATE_NO_WINDOW.0 | CREATE_NEW_PROCESS_GROUP.0);
+    }
+}
+
+/// 脚本进程启动后调用，Windows 上把它放进作业对象，之后启动的子进程也在同一个作业中
+#[cfg(target_os = "macos")]
+pub(crate) fn track_process(child: &Child) {
+    GROUPS.lock().unwrap().insert(child.id(), false);
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn track_process(child: &Child) {
+    if let Some(job) = Job::new(child) {
+        JOBS.lock().unwrap().insert(child.id(), job);
+    }
+}
+
+/// 等待脚本进程退出，代替 `child.wait()`
+///
+/// macOS 上先等待退出但不回收，正在停止时等停止流程结束进程组中剩下的子进程后再回收，
+/// 在此之前进程组编号不会被其他进程重用。Windows 上正在停止时保留作业对象，由停止流程结束剩下的子进程。
+/// 正常退出时不结束子进程
+#[cfg(target_os = "macos")]
+pub(crate) fn wait_process(child: &mut Child) -> io::Result<ExitStatus> {
+    let pid = child.id();
+    // 无法只等待不回收时直接回收，停止流程仍然会等到回收前结束
+    let _ = wait_exited(pid);
+    {
+        let mut groups = GROUPS.lock().unwrap();
+        while groups.get(&pid) == Some(&true) {
+            groups = GROUPS_CHANGED.wait(groups).unwrap();
+        }
+        groups.remove(&pid);
+    }
+    child.wait()
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn wait_process(child: &mut Child) -> io::Result<ExitStatus> {
+    let status = child.wait();
+    untrack_process(child.id());
+    status
+}
+
+// 等待脚本进程退出，进程保留为僵尸进程，之后由 `child.wait()` 回收
+#[cfg(target_os = "macos")]
+fn wait_exited(pid: u32) -> io::Result<()> {
+    loop {
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let result = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+        if result == 0 {
+            return Ok(());
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+// 脚本进程已经退出但还没有被回收
+#[cfg(target_os = "macos")]
+fn has_exited(pid: u32) -> bool {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::waitid(
+            libc::P_PID,
+            pid as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOWAIT | libc::WNOHANG,
+        )
+    };
+    result == 0 && info.si_pid != 0
+}
+
+#[cfg(target_os = "windows")]
+fn has_exited(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn untrack_process(pid: u32) {
+    let mut jobs = JOBS.lock().unwrap();
+    if jobs.get(&pid).is_some_and(|job| !job.stopping) {
+        if let Some(job) = jobs.remove(&pid) {
+            job.detach();
+        }
+    }
+}
+
+/// 在后台线程中停止脚本进程及其子进程
+///
+/// 先请求退出，让脚本有机会松开按键和保存状态：macOS 向进程组发送 `SIGINT`，
+/// Windows 向进程组发送 `CTRL_BREAK_EVENT`。`grace` 内 `is_exited` 没有返回 `true` 时强制结束，
+/// macOS 结束整个进程组，Windows 结束整个作业；脚本已经退出时也会结束它留下的子进程。
+/// `grace` 为 0 时直接强制结束。macOS 上脚本进程已经被回收时不做任何事，不会向其他进程组发送信号。
+pub(crate) fn terminate_tree(
+    pid: u32,
+    grace: Duration,
+    is_exited: impl Fn() -> bool + Send + 'static,
+) {
+    #[cfg(target_os = "macos")]
+    {
+        match GROUPS.lock().unwrap().get_mut(&pid) {
+            Some(stopping) => *stopping = true,
+            None => return,
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(job) = JOBS.lock().unwrap().get_mut(&pid) {
+            job.stopping = true;
+        }
+    }
+    thread::spawn(move || {
+        if !grace.is_zero() {
+            interrupt(pid);
+            let deadline = Instant::now() + grace;
+            while !is_exited() && !has_exited(pid) && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(EXIT_POLL_MILLIS));
+            }
+        }
+        kill(pid);
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn interrupt(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGINT);
+    }
+}
+
+// 需要临时连接到脚本的控制台才能发送控制台事件，同时只能连接一个，之后恢复 Kiwi 原来的控制台
+#[cfg(target_os = "windows")]
+fn interrupt(pid: u32) {
+    let _guard = CONSOLE.lock().unwrap();
+    unsafe {
+        let _ = FreeConsole();
+        if AttachConsole(pid).is_err() {
+            return;
+        }
+        // Kiwi 自己忽略该事件
+        let _ = SetConsoleCtrlHandler(None, true);
+        let _ = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid);
+        let _ = FreeConsole();
+        let _ = SetConsoleCtrlHandler(None, false);
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+// 脚本已经退出时，macOS 仍然结束进程组中剩下的子进程。
+// 脚本进程在停止流程结束前不会被回收，进程组编号仍然属于它
+#[cfg(target_os = "macos")]
+fn kill(pid: u32) {
+    let mut groups = GROUPS.lock().unwrap();
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGKILL);
+    }
+    groups.remove(&pid);
+    GROUPS_CHANGED.notify_all();
+}
+
+// 结束作业中的所有进程，包括父进程已经退出的子进程
+#[cfg(target_os = "windows")]
+fn kill(pid: u32) {
+    if let Some(job) = JOBS.lock().unwrap().remove(&pid) {
+        job.terminate();
+    }
+}
+
+/// 由 Kiwi 创建、还没有被回收的脚本进程组，值为是否正在停止
+#[cfg(target_os = "macos")]
+static GROUPS: LazyLock<Mutex<HashMap<u32, bool>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(target_os = "macos")]
+static GROUPS_CHANGED: Condvar = Condvar::new();
+
+#[cfg(target_os = "windows")]
+static JOBS: LazyLock<Mutex<HashMap<u32, Job>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(target_os = "windows")]
+static CONSOLE: Mutex<()> = Mutex::new(());
+
+/// 包含脚本进程及其子进程的作业对象，关闭句柄时结束作业中的所有进程
+#[cfg(target_os = "windows")]
+struct Job {
+    // 保存为整数，句柄可以在线程之间传递
+    handle: isize,
+    stopping: bool,
+}
+
+#[cfg(target_os = "windows")]
+impl Job {
+    fn new(child: &Child) -> Option<Self> {
+        unsafe {
+            let handle = CreateJobObjectW(None, None).ok()?;
+            let job = Self {
+                handle: handle.0 as isize,
+                stopping: false,
+            };
+            let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            job.set_limit(&info).ok()?;
+            let process = HANDLE(child.as_raw_handle() as *mut c_void);
+            AssignProcessToJobObject(job.handle(), process).ok()?;
+            Some(job)
+        }
+    }
+
+    fn handle(&self) -> HANDLE {
+        HANDLE(self.handle as *mut c_void)
+    }
+
+    unsafe fn set_limit(
+        &self,
+        info: &JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    ) -> windows::core::Result<()> {
+        unsafe {
+            SetInformationJobObject(
+                self.handle(),
+                JobObjectExtendedLimitInformation,
+                info as *const _ as *const c_void,
+                size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+        }
+    }
+
+    fn terminate(self) {
+        unsafe {
+            let _ = TerminateJobObject(self.handle(), 1);
+        }
+    }
+
+    // 取消关闭时结束进程的限制，脚本正常退出后留下的子进程继续运行
+    fn detach(self) {
+        unsafe {
+            let _ = self.set_limit(&JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default());
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Job {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.handle());
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use super::TokenExt as _;
+use crate::input::{Chord, Token};
 use crate::interpreter::Code;
 use crate::types::{ColoredPoint, HexColor, Point, RgbOffset};
+use anyhow::Result;
 
 pub struct PythonCode {}
 
@@ -126,4 +129,38 @@ client = ScreenClient()
 )"#
         )
     }
+
+    fn move_to_found_image(
+        &self,
+        find_image: &str,
+        offset: &Point,
+        scale: (f64, f64),
+        fallback: &Point,
+    ) -> String {
+        let Point { x: ox, y: oy } = offset;
+        let (sx, sy) = scale;
+        let Point { x: fx, y: fy } = fallback;
+        format!(
+            r#"found = {find_image}.data
+client.move_absolute(
+    absolute_point=Point(x=round((found.point.x+{ox})/{sx:?}),y=round((found.point.y+{oy})/{sy:?})) if found else Point(x={fx},y={fy}),
+)"#
+        )
+    }
+
+    fn sleep(&self, millis: u64) -> String {
+        format!("System.sleep(milliseconds={millis})")
+    }
+
+    fn comment(&self, text: &str) -> String {
+        format!("# {text}")
+    }
+
+    fn hotkey(&self, chord: &Chord) -> Result<String> {
+        Ok(format!("client.hotkey(keys=\"{}\")", chord.to_hotkey()?))
+    }
+
+    fn token(&self, token: &Token) -> Result<String> {
+        Ok(format!("client.{}", token.to_python_statement()?))
+    }
 }
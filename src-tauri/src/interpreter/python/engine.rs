@@ -1,5 +1,9 @@
 // done
 use super::{PyProject, PythonCode};
+use crate::interpreter::{
+    RunOptions, ScriptExit, configure_process, forward_lines, terminate_tree, track_process,
+    wait_process,
+};
 use crate::{
     app::App,
     project::{Config, VerifyStatus},
@@ -10,11 +14,12 @@ use std::{
     fs::{self, File},
     io::{BufRead, BufReader, Write},
     path::{Path, PathBuf},
-    process::{ChildStderr, ChildStdout, Command, ExitStatus, Stdio},
+    process::{Command, Stdio},
     sync::{
         Arc,
         atomic::{AtomicU32, Ordering},
     },
+    time::Duration,
 };
 
 #[cfg(windows)]
@@ -379,13 +384,14 @@ impl Engine {
 
     pub fn run<
         OnSpawned: Fn(u32) + Send + 'static,
-        OnStdout: Fn(ChildStdout) + Send + 'static,
-        OnStderr: Fn(ChildStderr) + Send + 'static,
-        OnExit: Fn(u32, ExitStatus) + Send + 'static,
+        OnStdout: Fn(String) + Send + 'static,
+        OnStderr: Fn(String) + Send + 'static,
+        OnExit: Fn(u32, ScriptExit) + Send + 'static,
     >(
         &self,
         path: impl AsRef<Path>,
         port: u16,
+        options: &RunOptions,
         on_spawned: OnSpawned,
         on_stdout: OnStdout,
         on_stderr: OnStderr,
@@ -399,50 +405,33 @@ impl Engine {
             .arg(port.to_string())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        #[cfg(target_os = "windows")]
-        {
-            command.creation_flags(CREATE_NO_WINDOW.0);
-        }
+        options.apply(&mut command, &self.project_path);
+        configure_process(&mut command);
         let mut child = command.spawn()?;
+        track_process(&child);
         let pid = child.id();
-        on_spawned(pid);
         self.pid.store(pid, Ordering::SeqCst);
+        on_spawned(pid);
         if let Some(stdout) = child.stdout.take() {
-            on_stdout(stdout);
+            forward_lines(stdout, on_stdout);
         }
         if let Some(stderr) = child.stderr.take() {
-            on_stderr(stderr);
+            forward_lines(stderr, on_stderr);
         }
-        let exit_status = child.wait()?;
-        on_exit(pid, exit_status);
+        let exit_status = wait_process(&mut child)?;
+        on_exit(pid, ScriptExit::Process(exit_status));
         self.pid.store(0, Ordering::SeqCst);
         Ok(())
     }
 
-    pub fn stop(&self) -> bool {
+    /// 先请求脚本退出，`grace` 后仍未退出时强制结束脚本及其子进程，不等待进程结束
+    pub fn stop(&self, grace: Duration) -> bool {
         let pid = self.pid.load(Ordering::SeqCst);
         if pid == 0 {
             return false;
         }
-        #[cfg(target_os = "windows")]
-        {
-            let handle = Command::new("taskkill")
-                .arg("/F")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .creation_flags(CREATE_NO_WINDOW.0)
-                .spawn();
-            if let Ok(mut handle) = handle {
-                let _ = handle.wait();
-            }
-        }
-        #[cfg(target_os = "macos")]
-        {
-            unsafe {
-                libc::kill(pid as i32, libc::SIGKILL);
-            }
-        }
-        self.pid.store(0, Ordering::SeqCst);
+        let current = Arc::clone(&self.pid);
+        terminate_tree(pid, grace, move || current.load(Ordering::SeqCst) != pid);
         true
     }
 
@@ -115,6 +115,10 @@ impl TryFrom<PythonKey> for Key {
             "ScrollLock" => Key::ScrollLock,
             "Pause" => Key::Pause,
             "NumLock" => Key::NumLock,
+            "AltRight" => Key::AltRight,
+            "ControlRight" => Key::ControlRight,
+            "MetaRight" => Key::MetaRight,
+            "ShiftRight" => Key::ShiftRight,
             unknown_key => {
                 return Err(anyhow!(t!(
                     "The Python key is not supported.",
@@ -235,6 +239,10 @@ impl From<Key> for PythonKey {
             Key::ScrollLock => "ScrollLock",
             Key::Pause => "Pause",
             Key::NumLock => "NumLock",
+            Key::AltRight => "AltRight",
+            Key::ControlRight => "ControlRight",
+            Key::MetaRight => "MetaRight",
+            Key::ShiftRight => "ShiftRight",
         }
         .to_string()
     }
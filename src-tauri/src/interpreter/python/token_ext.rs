@@ -9,7 +9,7 @@ pub trait TokenExt {
 impl TokenExt for Token {
     fn to_python_statement(&self) -> Result<String> {
         let python_script = match self {
-            Token::Text(text) => format!("input_text(\"text={}\")", text),
+            Token::Text(text) => format!("input_text(text={})", python_string(text)),
             Token::Key(system_key, direction) => {
                 let key: Key = system_key.to_owned().try_into()?;
                 let python_key: PythonKey = key.try_into()?;
@@ -27,6 +27,9 @@ impl TokenExt for Token {
                 (Button::Right, Press) => format!("press_right()"),
                 (Button::Right, Release) => format!("release_right()"),
                 (Button::Right, Click) => format!("click_right()"),
+                (Button::Middle, Press) => format!("press_middle()"),
+                (Button::Middle, Release) => format!("release_middle()"),
+                (Button::Middle, Click) => format!("click_middle()"),
                 (_, _) => {
                     return Err(anyhow!(t!("Unsupported button action.")));
                 }
@@ -44,3 +47,20 @@ impl TokenExt for Token {
         Ok(python_script)
     }
 }
+
+// 转换为 Python 字符串字面量
+fn python_string(text: &str) -> String {
+    let mut literal = String::from("\"");
+    for char in text.chars() {
+        match char {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            char => literal.push(char),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,270 @@
+use crate::app::{App, Log};
+use crate::interpreter::RunOptions;
+use crate::project::ConfigRhai;
+use crate::record::{ReplayOptions, read_events, replay};
+use crate::run::RunId;
+use crate::types::Point;
+use crate::websocket::{self, Handler};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, INT, Map, Position};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// 等待时检查是否停止的间隔
+const SLEEP_SLICE_MILLIS: u64 = 10;
+
+/// 截图、找图和 OCR
+const CAPTURE_METHODS: &[&str] = &[
+    "find_image",
+    "find_images",
+    "find_relative_colors",
+    "find_colors",
+    "recognize_text",
+];
+
+/// 鼠标、键盘、剪贴板和输入设置
+const INPUT_METHODS: &[&str] = &[
+    "click_left",
+    "click_right",
+    "click_middle",
+    "double_click",
+    "click_at",
+    "drag",
+    "press_left",
+    "press_right",
+    "press_middle",
+    "release_left",
+    "release_right",
+    "release_middle",
+    "move_absolute",
+    "move_to",
+    "move_relative",
+    "get_mouse_location",
+    "get_mouse_buttons",
+    "get_pressed_keys",
+    "is_key_pressed",
+    "get_lock_keys",
+    "scroll_vertical",
+    "scroll_horizontal",
+    "press_key",
+    "release_key",
+    "click_key",
+    "hotkey",
+    "input_text",
+    "get_clipboard_text",
+    "set_clipboard_text",
+    "get_clipboard_image",
+    "get_input_mode",
+    "get_input_log",
+    "clear_input_log",
+    "get_input_region",
+    "set_input_humanize",
+    "clear_input_humanize",
+    "get_input_humanize",
+];
+
+/// 会写入文件的方法，只有允许访问文件系统时才注册
+const FILESYSTEM_METHODS: &[&str] = &["save_frame", "set_input_dry_run"];
+
+/// 修改输入模式和允许区域的方法，只有允许修改输入设置时才注册
+const INPUT_SETTING_METHODS: &[&str] =
+    &["set_input_mode", "set_input_region", "clear_input_region"];
+
+/// 注册脚本可以使用的全局函数
+///
+/// * Kiwi 的方法与 websocket 客户端的方法同名，参数为同名字段组成的对象映射，也可以不传参数，
+///   返回值为原来响应中的 `data`，`null` 转换为 `()`，出错时抛出异常。
+///   写文件的方法和修改输入模式、允许区域的方法按 `limits` 的设置注册
+/// * `sleep(milliseconds)` - 等待期间可以被停止
+/// * `replay(name)`、`replay(name, #{ speed, loops })` - 回放项目中的录制文件
+///
+/// `running` 变为 `false` 时，以上函数都会结束脚本。
+pub(super) fn register(
+    engine: &mut Engine,
+    project_path: &Path,
+    origin: Point,
+    limits: &ConfigRhai,
+    run_id: Option<RunId>,
+    running: Arc<AtomicBool>,
+) {
+    let filesystem_methods = if limits.allow_filesystem {
+        FILESYSTEM_METHODS
+    } else {
+        &[]
+    };
+    let input_setting_methods = if limits.allow_input_settings {
+        INPUT_SETTING_METHODS
+    } else {
+        &[]
+    };
+    for method in CAPTURE_METHODS
+        .iter()
+        .chain(INPUT_METHODS)
+        .chain(filesystem_methods)
+        .chain(input_setting_methods)
+    {
+        let Some(handler) = websocket::get_method_handler(method) else {
+            continue;
+        };
+        let without_args = Arc::clone(&running);
+        engine.register_fn(*method, move || {
+            call(&without_args, run_id, handler, serde_json::Value::Null)
+        });
+        let with_args = Arc::clone(&running);
+        engine.register_fn(*method, move |args: Map| {
+            let args = rhai::serde::from_dynamic(&args.into())?;
+            call(&with_args, run_id, handler, args)
+        });
+    }
+
+    let sleep_running = Arc::clone(&running);
+    engine.register_fn("sleep", move |milliseconds: INT| {
+        sleep(&sleep_running, milliseconds)
+    });
+
+    let recordings = Recordings {
+        project_path: project_path.to_path_buf(),
+        origin,
+        running,
+    };
+    let replay_once = recordings.clone();
+    engine.register_fn("replay", move |name: &str| {
+        replay_once.replay(name, Map::new())
+    });
+    engine.register_fn("replay", move |name: &str, options: Map| {
+        recordings.replay(name, options)
+    });
+}
+
+/// 注册 `args()` 和 `env(name)`
+///
+/// `env` 只返回运行选项中的环境变量，没有时返回 `()`，脚本读取不到 Kiwi 进程的环境变量。
+pub(super) fn register_run_options(engine: &mut Engine, options: &RunOptions) {
+    let args: Array = options.args.iter().map(|arg| arg.clone().into()).collect();
+    engine.register_fn("args", move || args.clone());
+    let env = options.env.clone();
+    engine.register_fn("env", move |name: &str| -> Dynamic {
+        env.get(name)
+            .map_or(Dynamic::UNIT, |value| value.clone().into())
+    });
+}
+
+pub(super) fn check_running(running: &AtomicBool) -> Result<(), Box<EvalAltResult>> {
+    if !running.load(Ordering::SeqCst) {
+        return Err(EvalAltResult::ErrorTerminated(
+            t!("The script has been stopped.").to_string().into(),
+            Position::NONE,
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn call(
+    running: &AtomicBool,
+    run_id: Option<RunId>,
+    handler: Handler,
+    args: serde_json::Value,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    check_running(running)?;
+    if let Some(run_id) = run_id {
+        websocket::record_call(run_id);
+    }
+    let data = handler(args).map_err(|error| error.to_string())?;
+    rhai::serde::to_dynamic(data)
+}
+
+fn sleep(running: &AtomicBool, milliseconds: INT) -> Result<(), Box<EvalAltResult>> {
+    let deadline = Instant::now() + Duration::from_millis(milliseconds.max(0) as u64);
+    loop {
+        check_running(running)?;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(());
+        }
+        thread::sleep(remaining.min(Duration::from_millis(SLEEP_SLICE_MILLIS)));
+    }
+}
+
+/// 只能回放项目目录中的 `.jsonl` 录制文件
+#[derive(Clone)]
+struct Recordings {
+    project_path: PathBuf,
+    origin: Point,
+    running: Arc<AtomicBool>,
+}
+
+impl Recordings {
+    fn replay(&self, name: &str, options: Map) -> Result<(), Box<EvalAltResult>> {
+        check_running(&self.running)?;
+        let path = self.resolve(name)?;
+        let options = self.options(&options)?;
+        let events = read_events(&path).map_err(|error| error.to_string())?;
+        let result = replay(&events, options, &self.running);
+        // 中途停止时可能还有按下的按键。与运行结束时相同，只在没有其他脚本运行时松开，
+        // 避免松开其他脚本按住的键，其他脚本运行时由最后一个结束的运行松开
+        let interrupted = !self.running.load(Ordering::SeqCst) || result.is_err();
+        if interrupted && App::with_runs(|runs| runs.active_count()) <= 1 {
+            if let Err(error) = App::input().release_all() {
+                Log::error(error.to_string()).send_to_app_log();
+            }
+        }
+        result.map_err(|error| error.to_string())?;
+        check_running(&self.running)
+    }
+
+    fn resolve(&self, name: &str) -> Result<PathBuf, Box<EvalAltResult>> {
+        let path = Path::new(name);
+        let mut components = path.components();
+        let is_file_name = matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        );
+        let is_jsonl = path
+            .extension()
+            .is_some_and(|extension| extension == "jsonl");
+        if !is_file_name || !is_jsonl {
+            return Err(t!("Invalid recording name.", name = name)
+                .to_string()
+                .into());
+        }
+        Ok(self.project_path.join(path))
+    }
+
+    fn options(&self, options: &Map) -> Result<ReplayOptions, Box<EvalAltResult>> {
+        let default = ReplayOptions::default();
+        let speed = match options.get("speed") {
+            Some(speed) => speed
+                .as_float()
+                .or_else(|_| speed.as_int().map(|speed| speed as f64))
+                .map_err(|_| t!("The replay speed is invalid.", speed = speed).to_string())?,
+            None => default.speed,
+        };
+        if speed <= 0.0 || !speed.is_finite() {
+            return Err(t!("The replay speed is invalid.", speed = speed)
+                .to_string()
+                .into());
+        }
+        // 一直回放会占用脚本，不允许为 0
+        let loops = match options.get("loops") {
+            Some(loops) => loops
+                .as_int()
+                .ok()
+                .filter(|loops| *loops > 0)
+                .and_then(|loops| u32::try_from(loops).ok())
+                .ok_or_else(|| t!("The replay loops are invalid.", loops = loops).to_string())?,
+            None => default.loops,
+        };
+        Ok(ReplayOptions {
+            speed,
+            loops,
+            origin: self.origin,
+        })
+    }
+}
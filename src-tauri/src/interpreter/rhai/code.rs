@@ -0,0 +1,171 @@
+use super::TokenExt as _;
+use super::token_ext::rhai_string;
+use crate::input::{Chord, Token};
+use crate::interpreter::Code;
+use crate::types::{ColoredPoint, HexColor, Point, RgbOffset};
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct RhaiCode {}
+
+impl Code for RhaiCode {
+    // Kiwi 的方法都注册为全局函数，不需要导入
+    fn predefined(&self) -> String {
+        format!("// Kiwi functions are global, e.g. find_image(#{{ ... }}) and sleep(1000).\n")
+    }
+
+    fn find_image(
+        &self,
+        subpath: &str,
+        start_point: &Point,
+        end_point: &Point,
+        threshold: f64,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let subpath = rhai_string(subpath);
+        format!(
+            r#"find_image(#{{
+  subpath: {subpath},
+  start_point: #{{ x: {sx}, y: {sy} }},
+  end_point: #{{ x: {ex}, y: {ey} }},
+  threshold: {threshold},
+}})"#
+        )
+    }
+
+    fn find_images(
+        &self,
+        subpath: &str,
+        start_point: &Point,
+        end_point: &Point,
+        threshold: f64,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let subpath = rhai_string(subpath);
+        format!(
+            r#"find_images(#{{
+  subpath: {subpath},
+  start_point: #{{ x: {sx}, y: {sy} }},
+  end_point: #{{ x: {ex}, y: {ey} }},
+  threshold: {threshold},
+}})"#
+        )
+    }
+
+    fn find_relative_colors(
+        &self,
+        vertex_hex: &str,
+        relative_points: &[ColoredPoint],
+        start_point: &Point,
+        end_point: &Point,
+        rgb_offset: &RgbOffset,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let RgbOffset { r, g, b } = rgb_offset;
+        let rows_str = relative_points
+            .iter()
+            .map(|p| {
+                let Point { x, y } = p.point;
+                let hex = p.clone().hex;
+                format!("    #{{ point: #{{ x: {x}, y: {y} }}, hex: \"{hex}\" }},")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            r#"find_relative_colors(#{{
+  vertex_hex: "{vertex_hex}",
+  colored_points: [
+{rows_str}
+  ],
+  start_point: #{{ x: {sx}, y: {sy} }},
+  end_point: #{{ x: {ex}, y: {ey} }},
+  rgb_offset: #{{ r: {r}, g: {g}, b: {b} }},
+}})"#
+        )
+    }
+
+    fn find_colors(
+        &self,
+        hex_colors: &[HexColor],
+        start_point: &Point,
+        end_point: &Point,
+        rgb_offset: &RgbOffset,
+    ) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        let RgbOffset { r, g, b } = rgb_offset;
+        let rows_str = hex_colors
+            .iter()
+            .map(|hex| format!("    \"{hex}\","))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            r#"find_colors(#{{
+  hex_colors: [
+{rows_str}
+  ],
+  start_point: #{{ x: {sx}, y: {sy} }},
+  end_point: #{{ x: {ex}, y: {ey} }},
+  rgb_offset: #{{ r: {r}, g: {g}, b: {b} }},
+}})"#
+        )
+    }
+
+    fn recognize_text(&self, start_point: &Point, end_point: &Point) -> String {
+        let Point { x: sx, y: sy } = start_point;
+        let Point { x: ex, y: ey } = end_point;
+        format!(
+            r#"recognize_text(#{{
+  start_point: #{{ x: {sx}, y: {sy} }},
+  end_point: #{{ x: {ex}, y: {ey} }},
+}})"#
+        )
+    }
+
+    // 放在代码块中，`found` 不会影响脚本中的其他变量
+    fn move_to_found_image(
+        &self,
+        find_image: &str,
+        offset: &Point,
+        scale: (f64, f64),
+        fallback: &Point,
+    ) -> String {
+        let Point { x: ox, y: oy } = offset;
+        let (sx, sy) = scale;
+        let Point { x: fx, y: fy } = fallback;
+        format!(
+            r#"{{
+  let found = {find_image};
+  move_absolute(#{{
+    absolute_point: if found != () {{
+      #{{
+        x: ((found.point.x + {ox}).to_float() / {sx:?}).round().to_int(),
+        y: ((found.point.y + {oy}).to_float() / {sy:?}).round().to_int(),
+      }}
+    }} else {{
+      #{{ x: {fx}, y: {fy} }}
+    }},
+  }});
+}}"#
+        )
+    }
+
+    fn sleep(&self, millis: u64) -> String {
+        format!("sleep({millis});")
+    }
+
+    fn comment(&self, text: &str) -> String {
+        format!("// {text}")
+    }
+
+    fn hotkey(&self, chord: &Chord) -> Result<String> {
+        Ok(format!("hotkey(#{{ keys: \"{}\" }});", chord.to_hotkey()?))
+    }
+
+    fn token(&self, token: &Token) -> Result<String> {
+        token.to_rhai_statement()
+    }
+}
@@ -0,0 +1,241 @@
+use super::{RhaiCode, api};
+use crate::{
+    app::App,
+    interpreter::{RunOptions, ScriptExit},
+    project::{Config, ConfigRhai, VerifyStatus},
+    types::Point,
+};
+use anyhow::{Result, anyhow};
+use fs_extra;
+use rhai::module_resolvers::{DummyModuleResolver, FileModuleResolver};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
+};
+
+/// 函数调用的最大深度
+const MAX_CALL_LEVELS: usize = 64;
+/// 表达式的最大嵌套深度，分别为全局和函数中
+const MAX_EXPR_DEPTHS: (usize, usize) = (64, 32);
+/// 字符串的最大长度
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+/// 数组和对象映射的最大元素数
+const MAX_COLLECTION_SIZE: usize = 100_000;
+
+/// 在 Kiwi 进程内以沙箱方式运行 Rhai 脚本
+///
+/// 脚本只能使用注册的截图、找图、输入和回放函数，执行的操作数和运行时间受项目配置 `[rhai]` 限制，
+/// 默认不能导入其他文件，也不能调用写文件的方法。停止或超时后，脚本在下一次操作时结束。
+#[derive(Clone)]
+pub struct Engine {
+    pub project_path: PathBuf,
+    running: Arc<AtomicBool>,
+}
+
+impl Engine {
+    pub fn new_from_project_path(project_path: impl AsRef<Path>) -> Self {
+        Self {
+            project_path: project_path.as_ref().to_path_buf(),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn verify_in_project(project_path: impl AsRef<Path>) -> VerifyStatus {
+        let project_path = project_path.as_ref();
+        // Check if main.rhai exists or VerifyStatus::Invalid
+        if !project_path.join("main.rhai").exists() {
+            return VerifyStatus::Invalid;
+        }
+        // Check if data/images exists or VerifyStatus::Invalid
+        if !project_path.join("data").join("images").exists() {
+            return VerifyStatus::Invalid;
+        }
+        VerifyStatus::Valid
+    }
+
+    // nothing is installed into the project, init again to restore missing files.
+    pub fn reinit(&self) -> Result<()> {
+        self.init()
+    }
+
+    pub fn init(&self) -> Result<()> {
+        //copy template to project, files edited by the user are kept.
+        {
+            let options = fs_extra::dir::CopyOptions::new()
+                .skip_exist(true)
+                .content_only(true);
+            let template_dir = App::get_resource_dir()
+                .join("rhai")
+                .join("project_template");
+            fs_extra::dir::copy(&template_dir, &self.project_path, &options)?;
+        }
+        fs::create_dir_all(self.project_path.join("data").join("images"))?;
+        Ok(())
+    }
+
+    /// 脚本在当前线程中执行，`port` 不会用到；运行期间以 Kiwi 的进程号作为 pid
+    pub fn run<
+        OnSpawned: Fn(u32) + Send + 'static,
+        OnStdout: Fn(String) + Send + 'static,
+        OnStderr: Fn(String) + Send + 'static,
+        OnExit: Fn(u32, ScriptExit) + Send + 'static,
+    >(
+        &self,
+        path: impl AsRef<Path>,
+        _port: u16,
+        options: &RunOptions,
+        on_spawned: OnSpawned,
+        on_stdout: OnStdout,
+        on_stderr: OnStderr,
+        on_exit: OnExit,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| {
+            anyhow!(t!(
+                "Unable to read the script.",
+                path = path.to_string_lossy(),
+                error = e.to_string()
+            ))
+        })?;
+        let config = Config::new_from_toml(&self.project_path)?;
+        let limits = config.rhai;
+        let origin = config.record.origin.unwrap_or(Point::new(0, 0));
+
+        self.running.store(true, Ordering::SeqCst);
+        let pid = std::process::id();
+        on_spawned(pid);
+
+        // 超时后与停止一样结束脚本，脚本结束时关闭通道让计时线程退出
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let (finished, watcher) = mpsc::channel::<()>();
+        if limits.timeout_secs > 0 {
+            let running = Arc::clone(&self.running);
+            let timed_out = Arc::clone(&timed_out);
+            let timeout = Duration::from_secs(limits.timeout_secs);
+            thread::spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = watcher.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    running.store(false, Ordering::SeqCst);
+                }
+            });
+        }
+
+        let result = self.execute(path, &source, &limits, origin, options, on_stdout);
+        drop(finished);
+        let exit = match result {
+            Ok(()) => ScriptExit::Finished,
+            Err(_) if timed_out.load(Ordering::SeqCst) => {
+                on_stderr(
+                    t!(
+                        "The script has exceeded the time limit.",
+                        seconds = limits.timeout_secs
+                    )
+                    .to_string(),
+                );
+                ScriptExit::Failed
+            }
+            Err(_) if !self.running.load(Ordering::SeqCst) => ScriptExit::Stopped,
+            Err(error) => {
+                for line in error.to_string().lines() {
+                    on_stderr(line.to_string());
+                }
+                ScriptExit::Failed
+            }
+        };
+        self.running.store(false, Ordering::SeqCst);
+        on_exit(pid, exit);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        path: &Path,
+        source: &str,
+        limits: &ConfigRhai,
+        origin: Point,
+        options: &RunOptions,
+        on_stdout: impl Fn(String) + 'static,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        let mut engine = rhai::Engine::new();
+        engine
+            .set_max_operations(limits.max_operations)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_COLLECTION_SIZE)
+            .set_max_map_size(MAX_COLLECTION_SIZE)
+            .disable_symbol("eval");
+        if limits.allow_filesystem {
+            engine.set_module_resolver(FileModuleResolver::new_with_path(&self.project_path));
+        } else {
+            engine.set_module_resolver(DummyModuleResolver::new());
+        }
+
+        let on_stdout = Arc::new(on_stdout);
+        let on_print = Arc::clone(&on_stdout);
+        engine.on_print(move |text| {
+            for line in text.lines() {
+                on_print(line.to_string());
+            }
+        });
+        engine.on_debug(move |text, _, position| {
+            on_stdout(format!("[{position}] {text}"));
+        });
+        let running = Arc::clone(&self.running);
+        engine.on_progress(move |_| {
+            api::check_running(&running)
+                .err()
+                .map(|_| rhai::Dynamic::UNIT)
+        });
+
+        api::register(
+            &mut engine,
+            &self.project_path,
+            origin,
+            limits,
+            options.run_id,
+            Arc::clone(&self.running),
+        );
+        api::register_run_options(&mut engine, options);
+
+        let mut ast = engine.compile(source)?;
+        let name = path.strip_prefix(&self.project_path).unwrap_or(path);
+        ast.set_source(name.to_string_lossy().to_string());
+        engine.run_ast(&ast)
+    }
+
+    /// 脚本在 Kiwi 进程内运行，不能强制结束，`grace` 不会用到
+    pub fn stop(&self, _grace: Duration) -> bool {
+        if !self.running.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.running.store(false, Ordering::SeqCst);
+        true
+    }
+
+    pub fn get_pid(&self) -> u32 {
+        if self.running.load(Ordering::SeqCst) {
+            std::process::id()
+        } else {
+            0
+        }
+    }
+}
+
+impl Engine {
+    pub fn code(&self) -> RhaiCode {
+        RhaiCode::default()
+    }
+}
+
+/// 脚本使用的是当前 Kiwi 提供的方法，版本与 Kiwi 相同
+pub fn get_kiwi_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
@@ -0,0 +1,67 @@
+use crate::input::{Abs, Button, Click, Horizontal, Key, Press, Rel, Release, Token, Vertical};
+use anyhow::{Result, anyhow};
+
+pub trait TokenExt {
+    fn to_rhai_statement(&self) -> Result<String>;
+}
+
+impl TokenExt for Token {
+    fn to_rhai_statement(&self) -> Result<String> {
+        let rhai_script = match self {
+            Token::Text(text) => format!("input_text(#{{ text: {} }})", rhai_string(text)),
+            Token::Key(system_key, direction) => {
+                let key: Key = system_key.to_owned().try_into()?;
+                match direction {
+                    Press => format!("press_key(#{{ key: \"{}\" }})", key),
+                    Release => format!("release_key(#{{ key: \"{}\" }})", key),
+                    Click => format!("click_key(#{{ key: \"{}\" }})", key),
+                }
+            }
+            Token::Button(button, direction) => match (button, direction) {
+                (Button::Left, Press) => format!("press_left()"),
+                (Button::Left, Release) => format!("release_left()"),
+                (Button::Left, Click) => format!("click_left()"),
+                (Button::Right, Press) => format!("press_right()"),
+                (Button::Right, Release) => format!("release_right()"),
+                (Button::Right, Click) => format!("click_right()"),
+                (Button::Middle, Press) => format!("press_middle()"),
+                (Button::Middle, Release) => format!("release_middle()"),
+                (Button::Middle, Click) => format!("click_middle()"),
+                (_, _) => {
+                    return Err(anyhow!(t!("Unsupported button action.")));
+                }
+            },
+            Token::MoveMouse(x, y, coordinate) => match coordinate {
+                Abs => format!(
+                    "move_absolute(#{{ absolute_point: #{{ x: {}, y: {} }} }})",
+                    x, y
+                ),
+                Rel => format!("move_relative(#{{ offset: #{{ x: {}, y: {} }} }})", x, y),
+            },
+            Token::Scroll(length, axis) => match axis {
+                Horizontal => format!("scroll_horizontal(#{{ length: {} }})", length),
+                Vertical => format!("scroll_vertical(#{{ length: {} }})", length),
+            },
+            _ => return Err(anyhow!(t!("Unsupported token."))),
+        };
+        Ok(format!("{};", rhai_script))
+    }
+}
+
+// 转换为 Rhai 字符串字面量
+pub(super) fn rhai_string(text: &str) -> String {
+    let mut literal = String::from("\"");
+    for char in text.chars() {
+        match char {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            char if char.is_control() => literal.push_str(&format!("\\u{:04X}", char as u32)),
+            char => literal.push(char),
+        }
+    }
+    literal.push('"');
+    literal
+}
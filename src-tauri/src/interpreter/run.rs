@@ -0,0 +1,82 @@
+use crate::run::RunId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::{BufRead as _, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    thread,
+};
+
+/// 传给外部解释器进程的运行编号，脚本连接 websocket 时带上它，Kiwi 按运行记录调用
+pub const RUN_ID_ENV: &str = "KIWI_RUN_ID";
+
+/// 运行脚本的参数、环境变量和工作目录
+///
+/// * `args` - 追加在 `--port <port>` 之后的命令行参数
+/// * `env` - 额外的环境变量，同名时覆盖 Kiwi 进程的环境变量
+/// * `cwd` - 工作目录，未设置时为项目目录
+/// * `run_id` - 运行编号，由开始运行时设置，不保存在项目配置中
+///
+/// 在 Kiwi 进程内运行的脚本不能切换工作目录，只使用 `args` 和 `env`。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunOptions {
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<PathBuf>,
+    #[serde(skip)]
+    pub run_id: Option<RunId>,
+}
+
+impl RunOptions {
+    /// 为外部解释器的进程设置参数、环境变量和工作目录
+    pub(crate) fn apply(&self, command: &mut Command, project_path: &Path) {
+        command
+            .args(&self.args)
+            .envs(&self.env)
+            .current_dir(self.cwd.as_deref().unwrap_or(project_path));
+        if let Some(run_id) = self.run_id {
+            command.env(RUN_ID_ENV, run_id.to_string());
+        }
+    }
+}
+
+/// 脚本结束时的状态
+///
+/// 外部解释器返回进程的退出状态，在 Kiwi 进程内运行的脚本返回执行结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptExit {
+    Process(ExitStatus),
+    Finished,
+    /// 脚本出错，错误信息已经输出到 stderr
+    Failed,
+    Stopped,
+}
+
+impl Display for ScriptExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptExit::Process(exit_status) => write!(f, "{}", exit_status),
+            ScriptExit::Finished => write!(f, "finished"),
+            ScriptExit::Failed => write!(f, "failed"),
+            ScriptExit::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// 在后台线程中逐行读取子进程的输出
+pub(crate) fn forward_lines(
+    reader: impl Read + Send + 'static,
+    on_line: impl Fn(String) + Send + 'static,
+) {
+    thread::spawn(move || {
+        let reader = BufReader::new(reader);
+        for line in reader.lines() {
+            if let Ok(line) = line {
+                on_line(line);
+            }
+        }
+    });
+}
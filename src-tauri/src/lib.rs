@@ -10,10 +10,13 @@ pub mod app;
 pub mod capture;
 pub mod commands;
 pub mod extensions;
+pub mod failsafe;
 pub mod input;
 pub mod interpreter;
 pub mod project;
 pub mod record;
+pub mod run;
+pub mod schedule;
 pub mod types;
 pub mod utils;
 pub mod websocket;
@@ -91,6 +94,16 @@ pub fn run() {
             commands::frontend::project::run_script,
             commands::frontend::project::run_recorder,
             commands::frontend::project::stop_all,
+            commands::frontend::project::stop_script,
+            commands::frontend::project::list_runs,
+            commands::frontend::project::get_run_log,
+            commands::frontend::schedule::list_schedules,
+            commands::frontend::schedule::save_schedule,
+            commands::frontend::schedule::remove_schedule,
+            commands::frontend::schedule::run_schedule,
+            commands::frontend::record::replay_record,
+            commands::frontend::record::stop_replay,
+            commands::frontend::record::convert_record,
             commands::frontend::frame::find_image,
             commands::frontend::frame::find_images,
             commands::frontend::frame::find_relative_colors,
@@ -1,17 +1,41 @@
 // done
+use crate::input::{Humanize, Region};
+use crate::record::Compression;
+use crate::run::Watchdog;
+use crate::schedule::Schedule;
+use crate::types::Point;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub project: ConfigProject,
+    #[serde(default)]
+    pub input: ConfigInput,
+    #[serde(default)]
+    pub record: ConfigRecord,
+    #[serde(default)]
+    pub rhai: ConfigRhai,
+    #[serde(default)]
+    pub run: ConfigRun,
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             project: ConfigProject::default(),
+            input: ConfigInput::default(),
+            record: ConfigRecord::default(),
+            rhai: ConfigRhai::default(),
+            run: ConfigRun::default(),
+            schedules: Vec::new(),
         }
     }
 }
@@ -38,9 +62,7 @@ impl Config {
         let content = fs::read_to_string(&path)?;
         let config: Config = toml::from_str(&content)?;
 
-        Ok(Self {
-            project: config.project,
-        })
+        Ok(config)
     }
 
     pub fn save(&self, project_path: impl AsRef<Path>) -> Result<()> {
@@ -71,3 +93,101 @@ impl Default for ConfigProject {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConfigInput {
+    /// 允许脚本输入的区域，例如目标程序的客户区
+    pub region: Option<Region>,
+    /// 拟人化输入设置，未设置时按原样输入
+    pub humanize: Option<Humanize>,
+}
+
+/// 录制设置
+///
+/// * `capture_images` - 按下鼠标按钮时截取模板图片，生成先查找图片再点击的脚本
+/// * `image_size` - 模板图片的边长（像素）
+/// * `image_threshold` - 查找模板图片时的相似度阈值
+/// * `region` - 只录制该区域内的鼠标输入，未设置时录制整个屏幕
+/// * `origin` - 录制事件中的坐标相对于该点，例如目标窗口的左上角，回放和转换为脚本时加回该点；未设置时为屏幕左上角
+/// * `exclude_self` - 丢弃针对 Kiwi 自身窗口的点击和按键
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfigRecord {
+    pub compression: Compression,
+    pub capture_images: bool,
+    pub image_size: u32,
+    pub image_threshold: f64,
+    pub region: Option<Region>,
+    pub origin: Option<Point>,
+    pub exclude_self: bool,
+}
+
+impl Default for ConfigRecord {
+    fn default() -> Self {
+        Self {
+            compression: Compression::default(),
+            capture_images: false,
+            image_size: 64,
+            image_threshold: 0.95,
+            region: None,
+            origin: None,
+            exclude_self: true,
+        }
+    }
+}
+
+/// Rhai 脚本的沙箱设置
+///
+/// * `max_operations` - 脚本最多执行的操作数，`0` 表示不限制
+/// * `timeout_secs` - 脚本最长运行时间（秒），`0` 表示不限制
+/// * `allow_filesystem` - 允许导入项目中的其他脚本，以及保存截图、输入日志等写文件的方法
+/// * `allow_input_settings` - 允许修改输入模式和允许区域，关闭时脚本不能解除项目配置的输入区域
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ConfigRhai {
+    pub max_operations: u64,
+    pub timeout_secs: u64,
+    pub allow_filesystem: bool,
+    pub allow_input_settings: bool,
+}
+
+impl Default for ConfigRhai {
+    fn default() -> Self {
+        Self {
+            max_operations: 10_000_000,
+            timeout_secs: 3600,
+            allow_filesystem: false,
+            allow_input_settings: false,
+        }
+    }
+}
+
+/// 运行设置
+///
+/// * `profile` - 运行时没有指定运行配置时使用的配置，未设置时不使用运行配置
+/// * `profiles` - 按名称保存的运行配置，例如 `[run.profiles.staging]`
+/// * `watchdog` - 运行配置没有设置 `watchdog` 时使用的运行监控
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ConfigRun {
+    pub profile: Option<String>,
+    pub profiles: BTreeMap<String, RunProfile>,
+    pub watchdog: Watchdog,
+}
+
+/// 运行配置，用同一个脚本运行不同的账号和环境
+///
+/// * `args` - 追加在脚本之后的命令行参数
+/// * `env` - 环境变量，覆盖项目 `.env` 和 `env_file` 中的同名变量
+/// * `env_file` - 相对于项目目录的环境变量文件，在项目的 `.env` 之后读取
+/// * `cwd` - 工作目录，相对路径基于项目目录
+/// * `watchdog` - 运行监控，例如 `[run.profiles.nightly.watchdog]`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RunProfile {
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub env_file: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub watchdog: Option<Watchdog>,
+}
@@ -0,0 +1,74 @@
+use anyhow::{Result, anyhow};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// 读取 `.env` 格式的环境变量文件
+///
+/// 每行一个 `KEY=VALUE`，支持 `#` 注释、`export ` 前缀和单双引号，
+/// 双引号中可以使用 `\n`、`\t`、`\"` 和 `\\` 转义，单引号中的内容按原样读取
+pub fn read_env_file(path: impl AsRef<Path>) -> Result<BTreeMap<String, String>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| {
+        anyhow!(t!(
+            "Unable to read the environment file.",
+            path = path.to_string_lossy(),
+            error = e.to_string()
+        ))
+    })?;
+    let mut env = BTreeMap::new();
+    for (index, line) in content.lines().enumerate() {
+        let invalid = || {
+            anyhow!(t!(
+                "Invalid line in the environment file.",
+                path = path.to_string_lossy(),
+                line = index + 1
+            ))
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        let is_valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_key {
+            return Err(invalid());
+        }
+        let value = parse_value(value.trim()).ok_or_else(invalid)?;
+        env.insert(key.to_string(), value);
+    }
+    Ok(env)
+}
+
+// 引号没有闭合时返回 None
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        let (quoted, _) = rest.split_once('\'')?;
+        return Some(quoted.to_string());
+    }
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut unescaped = String::new();
+        let mut chars = rest.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                '"' => return Some(unescaped),
+                '\\' => match chars.next()? {
+                    'n' => unescaped.push('\n'),
+                    'r' => unescaped.push('\r'),
+                    't' => unescaped.push('\t'),
+                    other => unescaped.push(other),
+                },
+                char => unescaped.push(char),
+            }
+        }
+        return None;
+    }
+    // 没有引号时，空白之后的 # 开始注释
+    let value = match value.find(" #").or_else(|| value.find("\t#")) {
+        Some(index) => &value[..index],
+        None => value,
+    };
+    Some(value.trim_end().to_string())
+}
@@ -1,5 +1,7 @@
 mod config;
+mod env_file;
 mod project;
 
 pub use config::*;
+pub use env_file::*;
 pub use project::*;
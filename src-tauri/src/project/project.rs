@@ -1,22 +1,28 @@
 // done
-use super::Config;
+use super::{Config, RunProfile, read_env_file};
 use crate::app::App;
-use crate::input::Token;
-use crate::interpreter::Interpreter;
-use anyhow::{Error, Result, anyhow};
+use crate::interpreter::{Interpreter, RunOptions};
+use crate::record::{events_to_script, read_events};
+use crate::run::Watchdog;
+use crate::schedule::Schedule;
+use crate::types::Point;
+use anyhow::{Result, anyhow};
 use fs_extra::dir::{DirOptions, get_dir_content2};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::fs::File;
-use std::io::BufWriter;
+use std::fs;
 use std::path::{MAIN_SEPARATOR, Path, PathBuf};
 use std::process::Command;
-use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
 
 pub type VerifyStatusString = String; // valid, invalid, moved
 
+/// 录制事件文件的后缀，与录制脚本同名
+pub const RECORD_EVENTS_SUFFIX: &str = "jsonl";
+
+/// 每次运行都会读取的项目环境变量文件，不存在时忽略
+pub const ENV_FILE: &str = ".env";
+
 pub struct Project {
     pub name: String,
     pub main_file: String,
@@ -50,6 +56,7 @@ pub struct ProjectInfo {
     pub main_file: String,
     pub path: String,
     pub kiwi_version: String,
+    pub run_profiles: Vec<String>,
 }
 
 impl From<&Project> for ProjectInfo {
@@ -60,6 +67,7 @@ impl From<&Project> for ProjectInfo {
             main_file: project.main_file.clone(),
             path: project.path.to_str().unwrap().to_string(),
             kiwi_version: project.interpreter.get_kiwi_version(),
+            run_profiles: project.config.run.profiles.keys().cloned().collect(),
         }
     }
 }
@@ -96,7 +104,10 @@ impl Project {
 
     pub fn get_kind(interpreter: &Interpreter) -> String {
         match interpreter {
-            Interpreter::Python(_) => {
+            Interpreter::Python(_)
+            | Interpreter::Javascript(_)
+            | Interpreter::Lua(_)
+            | Interpreter::Rhai(_) => {
                 return "desktop".to_string();
             }
         }
@@ -104,7 +115,10 @@ impl Project {
 
     pub fn get_edit_command(interpreter: &Interpreter) -> String {
         match interpreter {
-            Interpreter::Python(_) => {
+            Interpreter::Python(_)
+            | Interpreter::Javascript(_)
+            | Interpreter::Lua(_)
+            | Interpreter::Rhai(_) => {
                 #[cfg(target_os = "macos")]
                 return "open -n -a \"/Applications/Visual Studio Code.app\" \"${projectFolder}\""
                     .to_string();
@@ -152,15 +166,118 @@ impl Project {
         Ok(path)
     }
 
-    pub fn get_recorder_header_template(&self) -> String {
-        self.interpreter.get_recorder_header_template()
+    /// 按运行配置生成运行脚本的选项
+    ///
+    /// 环境变量依次读取项目的 `.env`、运行配置的 `env_file` 和 `env`，同名时后读取的覆盖先读取的。
+    /// `profile` 为 `None` 时使用项目配置中的默认运行配置。
+    pub fn run_options(&self, profile: Option<&str>) -> Result<RunOptions> {
+        let mut options = RunOptions::default();
+        let env_path = self.path.join(ENV_FILE);
+        if env_path.is_file() {
+            options.env.extend(read_env_file(&env_path)?);
+        }
+        let Some(profile) = self.run_profile(profile)? else {
+            return Ok(options);
+        };
+        if let Some(env_file) = &profile.env_file {
+            options.env.extend(read_env_file(self.path.join(env_file))?);
+        }
+        options.env.extend(profile.env.clone());
+        options.args = profile.args.clone();
+        if let Some(cwd) = &profile.cwd {
+            options.cwd = Some(self.working_dir(cwd)?);
+        }
+        Ok(options)
+    }
+
+    /// 运行配置中的运行监控，没有设置时使用 `[run.watchdog]`
+    pub fn run_watchdog(&self, profile: Option<&str>) -> Result<Watchdog> {
+        let watchdog = self
+            .run_profile(profile)?
+            .and_then(|profile| profile.watchdog)
+            .unwrap_or(self.config.run.watchdog);
+        Ok(watchdog)
+    }
+
+    fn run_profile(&self, profile: Option<&str>) -> Result<Option<&RunProfile>> {
+        let Some(name) = profile.or(self.config.run.profile.as_deref()) else {
+            return Ok(None);
+        };
+        match self.config.run.profiles.get(name) {
+            Some(profile) => Ok(Some(profile)),
+            None => Err(anyhow!(t!(
+                "The run profile does not exist.",
+                profile = name
+            ))),
+        }
+    }
+
+    /// 保存定时运行到项目配置，同名时替换
+    pub fn save_schedule(&mut self, schedule: Schedule) -> Result<()> {
+        schedule.validate()?;
+        let schedules = &mut self.config.schedules;
+        match schedules.iter_mut().find(|item| item.name == schedule.name) {
+            Some(item) => *item = schedule,
+            None => schedules.push(schedule),
+        }
+        self.config.save(&self.path)
+    }
+
+    /// 从项目配置中删除定时运行
+    pub fn remove_schedule(&mut self, name: &str) -> Result<()> {
+        let len = self.config.schedules.len();
+        self.config
+            .schedules
+            .retain(|schedule| schedule.name != name);
+        if self.config.schedules.len() == len {
+            return Err(anyhow!(t!("The schedule does not exist.", name = name)));
+        }
+        self.config.save(&self.path)
+    }
+
+    /// 相对路径基于项目目录，目录不存在时返回错误
+    pub fn working_dir(&self, cwd: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = self.path.join(cwd);
+        if !path.is_dir() {
+            return Err(anyhow!(t!(
+                "The working directory does not exist.",
+                path = path.to_string_lossy()
+            )));
+        }
+        Ok(path)
+    }
+
+    /// 将录制事件文件转换为当前解释器的脚本，保存为新的录制文件
+    pub fn convert_record_events(&self, events_path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = self.generate_record_file()?;
+        self.write_record_script(events_path, &path)?;
+        Ok(path)
     }
 
-    pub fn get_recorder_token_handler(
+    /// 录制结束后按项目配置压缩录制事件，并重新生成录制脚本
+    pub fn finish_record(&self, script_path: impl AsRef<Path>) -> Result<()> {
+        let script_path = script_path.as_ref();
+        let events_path = script_path.with_extension(RECORD_EVENTS_SUFFIX);
+        self.write_record_script(events_path, script_path)
+    }
+
+    fn write_record_script(
         &self,
-        error_handler: Box<dyn Fn(Error) + Send + Sync + 'static>,
-    ) -> Box<dyn Fn(Arc<Mutex<Instant>>, Vec<Token>, Arc<Mutex<BufWriter<File>>>) + Send> {
-        self.interpreter.get_recorder_token_handler(error_handler)
+        events_path: impl AsRef<Path>,
+        script_path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let compression = self.config.record.compression;
+        let events = compression.apply(read_events(events_path)?);
+        let code = self.interpreter.get_code();
+        let origin = self.config.record.origin.unwrap_or(Point::new(0, 0));
+        let script = events_to_script(
+            &events,
+            code.as_ref(),
+            compression.min_sleep_millis,
+            &origin,
+        )?;
+        fs::write(script_path, script)?;
+        Ok(())
     }
 
     pub fn reveal_folder(&self) {
@@ -0,0 +1,150 @@
+use crate::input::{Chord, Key, Press, Release, SystemKey, Token};
+
+/// 录制到的一次输入
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordInput {
+    Token(Token),
+    /// 按住修饰键再按普通按键，生成一次热键调用
+    Hotkey(Chord),
+}
+
+/// 录制时跟踪修饰键的状态
+///
+/// * 按住修饰键再按普通按键时，合并为一个组合键，例如 `ctrl+c`
+/// * 按住 Shift（或 AltGr）输入的字符、受 Caps Lock 或键盘布局影响的字符，按实际输入的文本记录
+/// * 修饰键单独按下又松开时，按原样记录按下和松开
+/// * Caps Lock 本身不记录，它对输入的影响已经体现在记录的文本中
+#[derive(Default)]
+pub struct ChordTracker {
+    /// 按下但还没有写入录制的修饰键
+    modifiers: Vec<SystemKey>,
+    /// 已经写入按下事件的修饰键，松开时也要写入
+    pressed: Vec<SystemKey>,
+    /// 已经用于组合键或文本的修饰键，松开时不再写入
+    used: Vec<SystemKey>,
+    /// 已经合并为组合键或文本的按键，松开时丢弃
+    consumed: Vec<SystemKey>,
+}
+
+impl ChordTracker {
+    /// `name` 为这次按键实际输入的字符，没有时为 `None`
+    pub fn track(&mut self, tokens: Vec<Token>, name: Option<&str>) -> Vec<RecordInput> {
+        let mut inputs = Vec::new();
+        for token in tokens {
+            match token {
+                Token::Key(key, Press) => self.press(key, name, &mut inputs),
+                Token::Key(key, Release) => self.release(key, &mut inputs),
+                Token::MoveMouse(..) => inputs.push(RecordInput::Token(token)),
+                token => {
+                    // 按住修饰键点击或滚动，例如 ctrl+点击，修饰键按原样写入
+                    self.flush(&mut inputs);
+                    inputs.push(RecordInput::Token(token));
+                }
+            }
+        }
+        inputs
+    }
+
+    fn press(&mut self, system_key: SystemKey, name: Option<&str>, inputs: &mut Vec<RecordInput>) {
+        let Ok(key) = Key::try_from(system_key) else {
+            inputs.push(RecordInput::Token(Token::Key(system_key, Press)));
+            return;
+        };
+        if key == Key::CapsLock {
+            self.consume(system_key);
+            return;
+        }
+        if key.is_modifier() {
+            if !self.modifiers.contains(&system_key) && !self.pressed.contains(&system_key) {
+                self.modifiers.push(system_key);
+            }
+            return;
+        }
+        if let Some(text) = self.typed_text(key, name) {
+            self.use_modifiers();
+            self.consume(system_key);
+            inputs.push(RecordInput::Token(Token::Text(text)));
+            return;
+        }
+        if !self.modifiers.is_empty() && self.pressed.is_empty() {
+            let chord = Chord {
+                modifiers: self.modifiers.clone(),
+                key: system_key,
+            };
+            self.use_modifiers();
+            self.consume(system_key);
+            inputs.push(RecordInput::Hotkey(chord));
+            return;
+        }
+        self.flush(inputs);
+        inputs.push(RecordInput::Token(Token::Key(system_key, Press)));
+    }
+
+    fn release(&mut self, system_key: SystemKey, inputs: &mut Vec<RecordInput>) {
+        if self.consumed.contains(&system_key) {
+            self.consumed.retain(|consumed| *consumed != system_key);
+            return;
+        }
+        if self.pressed.contains(&system_key) {
+            self.pressed.retain(|pressed| *pressed != system_key);
+        } else if self.modifiers.contains(&system_key) {
+            self.modifiers.retain(|modifier| *modifier != system_key);
+            let used = self.used.contains(&system_key);
+            self.used.retain(|used| *used != system_key);
+            if used {
+                return;
+            }
+            // 单独按下又松开的修饰键
+            inputs.push(RecordInput::Token(Token::Key(system_key, Press)));
+        }
+        inputs.push(RecordInput::Token(Token::Key(system_key, Release)));
+    }
+
+    fn flush(&mut self, inputs: &mut Vec<RecordInput>) {
+        for modifier in self.modifiers.drain(..) {
+            self.used.retain(|used| *used != modifier);
+            self.pressed.push(modifier);
+            inputs.push(RecordInput::Token(Token::Key(modifier, Press)));
+        }
+    }
+
+    fn use_modifiers(&mut self) {
+        for modifier in self.modifiers.iter() {
+            if !self.used.contains(modifier) {
+                self.used.push(*modifier);
+            }
+        }
+    }
+
+    fn consume(&mut self, system_key: SystemKey) {
+        if !self.consumed.contains(&system_key) {
+            self.consumed.push(system_key);
+        }
+    }
+
+    // 只按住 Shift 或 AltGr 时输入的字符按文本记录；没有按修饰键时，只有输入的字符与按键本身不同才按文本记录，
+    // 其余按键保持按下和松开，回放时长按的效果不变
+    fn typed_text(&self, key: Key, name: Option<&str>) -> Option<String> {
+        let name = name.filter(|name| !name.is_empty() && !name.chars().any(char::is_control))?;
+        let modifiers: Vec<Key> = self
+            .modifiers
+            .iter()
+            .chain(self.pressed.iter())
+            .filter_map(|modifier| Key::try_from(*modifier).ok())
+            .collect();
+        if modifiers.is_empty() {
+            return match key.to_char() {
+                Some(char) if name != char.to_string() => Some(name.to_string()),
+                _ => None,
+            };
+        }
+        // Windows 上按下 AltGr 时会同时收到左 Control
+        let alt_gr = modifiers.contains(&Key::AltRight);
+        let text_only = modifiers.iter().all(|modifier| match modifier.generic() {
+            Key::Shift => true,
+            Key::Control => alt_gr,
+            _ => *modifier == Key::AltRight,
+        });
+        text_only.then(|| name.to_string())
+    }
+}
@@ -0,0 +1,224 @@
+use super::{RecordEvent, RecordEventKind};
+use crate::input::Key;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr as _;
+
+/// 录制结束后对事件的压缩设置，每一步都可以单独开关
+///
+/// * `simplify_paths` - 用 Ramer–Douglas–Peucker 算法简化鼠标轨迹，`path_tolerance` 为允许的偏差（像素）
+/// * `fold_clicks` - 将按下、松开合并为点击，间隔不超过 `double_click_millis` 的点击合并为双击
+/// * `merge_text` - 将连续的可打印按键合并为一次文本输入
+/// * `min_sleep_millis` - 生成脚本时省略短于该值的等待，省略的时长累加到下一次等待
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Compression {
+    pub simplify_paths: bool,
+    pub path_tolerance: f64,
+    pub fold_clicks: bool,
+    pub double_click_millis: u64,
+    pub merge_text: bool,
+    pub min_sleep_millis: u64,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            simplify_paths: true,
+            path_tolerance: 3.0,
+            fold_clicks: true,
+            double_click_millis: 500,
+            merge_text: true,
+            min_sleep_millis: 50,
+        }
+    }
+}
+
+impl Compression {
+    pub fn apply(&self, events: Vec<RecordEvent>) -> Vec<RecordEvent> {
+        let mut events = events;
+        if self.simplify_paths {
+            events = simplify_paths(events, self.path_tolerance);
+        }
+        if self.fold_clicks {
+            events = fold_clicks(events, self.double_click_millis);
+        }
+        if self.merge_text {
+            events = merge_text(events);
+        }
+        events
+    }
+}
+
+fn simplify_paths(events: Vec<RecordEvent>, tolerance: f64) -> Vec<RecordEvent> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut path: Vec<RecordEvent> = Vec::new();
+    for event in events {
+        if matches!(event.kind, RecordEventKind::MouseMove { .. }) {
+            path.push(event);
+            continue;
+        }
+        result.extend(simplify_path(std::mem::take(&mut path), tolerance));
+        result.push(event);
+    }
+    result.extend(simplify_path(path, tolerance));
+    result
+}
+
+// 连续的鼠标移动视为一条轨迹，保留起点、终点和偏差超过 `tolerance` 的转折点
+fn simplify_path(path: Vec<RecordEvent>, tolerance: f64) -> Vec<RecordEvent> {
+    if path.len() < 3 {
+        return path;
+    }
+    let points: Vec<(f64, f64)> = path
+        .iter()
+        .map(|event| match event.kind {
+            RecordEventKind::MouseMove { x, y } => (x as f64, y as f64),
+            _ => unreachable!(),
+        })
+        .collect();
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let mut farthest = (0.0, start);
+        for (index, point) in points.iter().enumerate().take(end).skip(start + 1) {
+            let distance = perpendicular_distance(*point, points[start], points[end]);
+            if distance > farthest.0 {
+                farthest = (distance, index);
+            }
+        }
+        if farthest.0 > tolerance {
+            keep[farthest.1] = true;
+            stack.push((start, farthest.1));
+            stack.push((farthest.1, end));
+        }
+    }
+    path.into_iter()
+        .zip(keep)
+        .filter_map(|(event, keep)| keep.then_some(event))
+        .collect()
+}
+
+fn perpendicular_distance(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return ((point.0 - start.0).powi(2) + (point.1 - start.1).powi(2)).sqrt();
+    }
+    (dy * point.0 - dx * point.1 + end.0 * start.1 - end.1 * start.0).abs() / length
+}
+
+// 按下后紧接着松开同一个按钮才视为点击，中间有移动的是拖拽，保持原样
+fn fold_clicks(events: Vec<RecordEvent>, double_click_millis: u64) -> Vec<RecordEvent> {
+    let mut result: Vec<RecordEvent> = Vec::with_capacity(events.len());
+    let mut iter = events.into_iter().peekable();
+    while let Some(event) = iter.next() {
+        let RecordEventKind::ButtonPress { button } = &event.kind else {
+            result.push(event);
+            continue;
+        };
+        let released = matches!(
+            iter.peek().map(|next| &next.kind),
+            Some(RecordEventKind::ButtonRelease { button: released }) if released == button
+        );
+        if !released {
+            result.push(event);
+            continue;
+        }
+        let release = iter.next().unwrap();
+        let repeated = matches!(
+            result.last(),
+            Some(RecordEvent {
+                time,
+                kind: RecordEventKind::Click { button: last_button, .. },
+            }) if last_button == button && event.time.saturating_sub(*time) <= double_click_millis
+        );
+        if repeated {
+            if let Some(RecordEvent {
+                time,
+                kind: RecordEventKind::Click { count, .. },
+            }) = result.last_mut()
+            {
+                *count += 1;
+                *time = release.time;
+            }
+            continue;
+        }
+        let kind = RecordEventKind::Click {
+            button: button.to_owned(),
+            count: 1,
+        };
+        result.push(RecordEvent::new(release.time, kind));
+    }
+    result
+}
+
+// 没有按住修饰键时，连续的可打印按键和文本合并为一次文本输入，快速输入时按键的按下和松开可能交错
+fn merge_text(events: Vec<RecordEvent>) -> Vec<RecordEvent> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut text: Option<RecordEvent> = None;
+    let mut typed: Vec<String> = Vec::new();
+    let mut modifiers: Vec<String> = Vec::new();
+    for event in events {
+        match &event.kind {
+            RecordEventKind::KeyPress { key } if modifiers.is_empty() => {
+                if let Some(char) = printable_char(key) {
+                    match text.as_mut() {
+                        Some(RecordEvent {
+                            kind: RecordEventKind::Text { text: merged },
+                            ..
+                        }) => merged.push(char),
+                        _ => {
+                            let kind = RecordEventKind::Text {
+                                text: char.to_string(),
+                            };
+                            text = Some(RecordEvent::new(event.time, kind));
+                        }
+                    }
+                    typed.push(key.to_owned());
+                    continue;
+                }
+            }
+            // 录制时已经按实际输入记录的文本，例如按住 Shift 输入的字符
+            RecordEventKind::Text { text: typed_text } if modifiers.is_empty() => {
+                match text.as_mut() {
+                    Some(RecordEvent {
+                        kind: RecordEventKind::Text { text: merged },
+                        ..
+                    }) => merged.push_str(typed_text),
+                    _ => text = Some(event.clone()),
+                }
+                continue;
+            }
+            RecordEventKind::KeyRelease { key } => {
+                if let Some(index) = typed.iter().position(|typed_key| typed_key == key) {
+                    typed.remove(index);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        result.extend(text.take());
+        match &event.kind {
+            RecordEventKind::KeyPress { key } if is_modifier(key) => {
+                modifiers.push(key.to_owned());
+            }
+            RecordEventKind::KeyRelease { key } => {
+                modifiers.retain(|modifier| modifier != key);
+            }
+            _ => {}
+        }
+        result.push(event);
+    }
+    result.extend(text);
+    result
+}
+
+fn is_modifier(key: &str) -> bool {
+    Key::from_str(key).is_ok_and(|key| key.is_modifier())
+}
+
+fn printable_char(key: &str) -> Option<char> {
+    Key::from_str(key).ok()?.to_char()
+}
@@ -0,0 +1,66 @@
+use super::{RecordEvent, RecordEventKind};
+use crate::input::parse_hotkey;
+use crate::interpreter::Code;
+use crate::types::Point;
+use anyhow::Result;
+
+/// 将录制事件转换为 `code` 对应语言的脚本
+///
+/// 短于 `min_sleep_millis` 的等待会被省略，省略的时长累加到下一次等待。
+/// 事件中的鼠标坐标相对于录制时的原点，加上 `origin` 后写入脚本，与回放时相同
+pub fn events_to_script(
+    events: &[RecordEvent],
+    code: &dyn Code,
+    min_sleep_millis: u64,
+    origin: &Point,
+) -> Result<String> {
+    let mut lines = vec![code.predefined().trim_end().to_string()];
+    let mut last_time = 0;
+    for event in events {
+        let event = &event.translated(origin.x, origin.y);
+        let millis = event.time.saturating_sub(last_time);
+        if millis >= min_sleep_millis.max(1) {
+            lines.push(code.sleep(millis));
+            last_time = event.time;
+        }
+        if let RecordEventKind::Image {
+            subpath,
+            x,
+            y,
+            offset_x,
+            offset_y,
+            screen_width,
+            screen_height,
+            threshold,
+            scale_x,
+            scale_y,
+        } = &event.kind
+        {
+            let end_point = Point::new(*screen_width as i32 - 1, *screen_height as i32 - 1);
+            let find_image = code.find_image(subpath, &Point::new(0, 0), &end_point, *threshold);
+            let offset = Point::new(*offset_x, *offset_y);
+            let fallback = Point::new(*x, *y);
+            lines.push(code.move_to_found_image(
+                &find_image,
+                &offset,
+                (*scale_x, *scale_y),
+                &fallback,
+            ));
+        }
+        if let RecordEventKind::Marker { label } = &event.kind {
+            lines.push(code.comment(label));
+        }
+        if let RecordEventKind::Hotkey { keys } = &event.kind {
+            for chord in parse_hotkey(keys)? {
+                lines.push(code.hotkey(&chord)?);
+            }
+            continue;
+        }
+        for token in event.to_tokens()? {
+            lines.push(code.token(&token)?);
+        }
+    }
+    let mut script = lines.join("\n");
+    script.push('\n');
+    Ok(script)
+}
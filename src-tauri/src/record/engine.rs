@@ -1,3 +1,5 @@
+use super::{ChordTracker, HotkeyFilter, RecordAction, RecordInput, RegionFilter, ScriptWriter};
+use crate::app::Log;
 use crate::input;
 use anyhow::Result;
 use enigo::agent::Token;
@@ -5,8 +7,7 @@ use enigo::agent::Token;
 use rdev::set_is_main_thread;
 use rdev::{Button, Event, EventType, listen, stop_listen};
 use std::{
-    fs::File,
-    io::BufWriter,
+    path::PathBuf,
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, Ordering},
@@ -16,27 +17,61 @@ use std::{
 };
 pub struct Engine {
     running: Arc<AtomicBool>,
+    /// 当前录制的脚本文件，录制结束后用于压缩事件并重新生成脚本
+    file: Mutex<Option<PathBuf>>,
 }
 
 impl Engine {
-    pub fn start<F>(
+    /// 开始录制，输入经过 `filter` 过滤热键、`region` 按位置过滤、合并组合键后交给 `token_handler`，
+    /// 热键触发的操作交给 `action_handler`；`token_handler` 返回错误（例如写入文件失败）时停止录制
+    pub fn start<F, A>(
         &self,
         last_call_time: Arc<Mutex<Instant>>,
+        filter: HotkeyFilter,
+        region: RegionFilter,
         token_handler: F,
-        writer: Arc<Mutex<BufWriter<File>>>,
+        action_handler: A,
+        writer: Arc<Mutex<ScriptWriter>>,
     ) where
-        F: 'static + Fn(Arc<Mutex<Instant>>, Vec<Token>, Arc<Mutex<BufWriter<File>>>) + Send,
+        F: 'static
+            + Fn(Arc<Mutex<Instant>>, Vec<RecordInput>, Arc<Mutex<ScriptWriter>>) -> Result<()>
+            + Send,
+        A: 'static + Fn(RecordAction) + Send,
     {
         self.stop();
         let running = Arc::clone(&self.running);
+        let filter = Mutex::new(filter);
+        let region = Mutex::new(region);
+        let tracker = Mutex::new(ChordTracker::default());
         thread::spawn(move || {
             running.store(true, Ordering::SeqCst);
             let callback = move |event: Event| {
                 let last_call_time = Arc::clone(&last_call_time);
                 let writer = Arc::clone(&writer);
-                // 在mac上监听不到 Caps lock todo
-                if let Ok(tokens) = event.event_type.to_token() {
-                    token_handler(last_call_time, tokens, writer);
+                // 在mac上监听不到 Caps lock，输入的字符按 `event.name` 记录，不受影响
+                let Ok(tokens) = event.event_type.to_token() else {
+                    return;
+                };
+                let (tokens, action) = filter.lock().unwrap().filter(tokens);
+                if let Some(action) = action {
+                    // 暂停期间的时长不计入等待
+                    if action == RecordAction::Resume {
+                        *last_call_time.lock().unwrap() = Instant::now();
+                    }
+                    action_handler(action);
+                }
+                let tokens = region.lock().unwrap().filter(tokens);
+                if tokens.is_empty() {
+                    return;
+                }
+                let inputs = tracker.lock().unwrap().track(tokens, event.name.as_deref());
+                if inputs.is_empty() {
+                    return;
+                }
+                if let Err(error) = token_handler(last_call_time, inputs, writer) {
+                    let msg = t!("Recorder error occurred.", error = error.to_string());
+                    Log::error(msg.to_string()).send_to_app_log();
+                    stop_listen();
                 }
             };
 
@@ -56,12 +91,21 @@ impl Engine {
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
     }
+
+    pub fn set_file(&self, file: PathBuf) {
+        *self.file.lock().unwrap() = Some(file);
+    }
+
+    pub fn take_file(&self) -> Option<PathBuf> {
+        self.file.lock().unwrap().take()
+    }
 }
 
 impl Default for Engine {
     fn default() -> Self {
         Self {
             running: Arc::new(AtomicBool::new(false)),
+            file: Mutex::new(None),
         }
     }
 }
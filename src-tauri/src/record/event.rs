@@ -0,0 +1,246 @@
+use super::RecordInput;
+use crate::input::{
+    Abs, Button, Click, Horizontal, Key, Press, Release, SystemKey, Token, Vertical, parse_button,
+    parse_hotkey,
+};
+use crate::types::Point;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead as _, BufReader, BufWriter, Write as _},
+    path::Path,
+    str::FromStr as _,
+};
+
+/// 录制文件中的一个事件，按 JSONL 格式每行保存一个
+///
+/// `time` 为距离录制开始的毫秒数，例如：
+/// `{"time":1200,"type":"key_press","key":"A"}`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecordEvent {
+    pub time: u64,
+    #[serde(flatten)]
+    pub kind: RecordEventKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordEventKind {
+    KeyPress {
+        key: String,
+    },
+    KeyRelease {
+        key: String,
+    },
+    ButtonPress {
+        button: String,
+    },
+    ButtonRelease {
+        button: String,
+    },
+    MouseMove {
+        x: i32,
+        y: i32,
+    },
+    Scroll {
+        dx: i32,
+        dy: i32,
+    },
+    /// 以下事件由录制后的压缩生成
+    Click {
+        button: String,
+        count: u32,
+    },
+    Text {
+        text: String,
+    },
+    /// 组合键，`keys` 的格式与 `hotkey` 方法相同，例如 `Control+C`
+    Hotkey {
+        keys: String,
+    },
+    /// 按下鼠标按钮时截取的模板图片，`subpath` 相对于图片目录，`offset_x`/`offset_y` 为点击位置在图片中的偏移（像素）。
+    /// `scale_x`/`scale_y` 为截图像素与鼠标坐标的比例，找到图片后用它转换回鼠标坐标
+    Image {
+        subpath: String,
+        x: i32,
+        y: i32,
+        offset_x: i32,
+        offset_y: i32,
+        screen_width: u32,
+        screen_height: u32,
+        threshold: f64,
+        #[serde(default = "default_scale")]
+        scale_x: f64,
+        #[serde(default = "default_scale")]
+        scale_y: f64,
+    },
+    /// 录制时通过热键插入的标记，转换为脚本时生成注释
+    Marker {
+        label: String,
+    },
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl RecordEvent {
+    pub fn new(time: u64, kind: RecordEventKind) -> Self {
+        Self { time, kind }
+    }
+
+    /// 将录制时产生的输入转换为事件，不支持的输入返回 `None`
+    pub fn from_token(time: u64, token: &Token) -> Option<Self> {
+        let kind = match token {
+            Token::Key(system_key, direction) => {
+                let key = Key::try_from(*system_key).ok()?.to_string();
+                match direction {
+                    Press => RecordEventKind::KeyPress { key },
+                    Release => RecordEventKind::KeyRelease { key },
+                    Click => return None,
+                }
+            }
+            Token::Button(button, direction) => {
+                let button = button_name(button)?.to_string();
+                match direction {
+                    Press => RecordEventKind::ButtonPress { button },
+                    Release => RecordEventKind::ButtonRelease { button },
+                    Click => RecordEventKind::Click { button, count: 1 },
+                }
+            }
+            Token::MoveMouse(x, y, _) => RecordEventKind::MouseMove { x: *x, y: *y },
+            Token::Scroll(length, Horizontal) => RecordEventKind::Scroll { dx: *length, dy: 0 },
+            Token::Scroll(length, Vertical) => RecordEventKind::Scroll { dx: 0, dy: *length },
+            Token::Text(text) => RecordEventKind::Text {
+                text: text.to_owned(),
+            },
+            _ => return None,
+        };
+        Some(Self::new(time, kind))
+    }
+
+    pub fn from_input(time: u64, input: &RecordInput) -> Option<Self> {
+        match input {
+            RecordInput::Token(token) => Self::from_token(time, token),
+            RecordInput::Hotkey(chord) => {
+                let keys = chord.to_hotkey().ok()?;
+                Some(Self::new(time, RecordEventKind::Hotkey { keys }))
+            }
+        }
+    }
+
+    /// 鼠标坐标加上 `dx`、`dy`，用于在相对于原点的坐标和屏幕坐标之间转换
+    pub fn translated(&self, dx: i32, dy: i32) -> Self {
+        let mut event = self.clone();
+        match &mut event.kind {
+            RecordEventKind::MouseMove { x, y } | RecordEventKind::Image { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            _ => {}
+        }
+        event
+    }
+
+    pub fn to_tokens(&self) -> Result<Vec<Token>> {
+        let tokens = match &self.kind {
+            RecordEventKind::KeyPress { key } => {
+                vec![Token::Key(SystemKey::from(Key::from_str(key)?), Press)]
+            }
+            RecordEventKind::KeyRelease { key } => {
+                vec![Token::Key(SystemKey::from(Key::from_str(key)?), Release)]
+            }
+            RecordEventKind::ButtonPress { button } => {
+                vec![Token::Button(parse_button(button)?, Press)]
+            }
+            RecordEventKind::ButtonRelease { button } => {
+                vec![Token::Button(parse_button(button)?, Release)]
+            }
+            RecordEventKind::MouseMove { x, y } => {
+                vec![Token::MoveMouse(*x, *y, Abs)]
+            }
+            RecordEventKind::Scroll { dx, dy } => {
+                let mut tokens = Vec::new();
+                if *dx != 0 {
+                    tokens.push(Token::Scroll(*dx, Horizontal));
+                }
+                if *dy != 0 {
+                    tokens.push(Token::Scroll(*dy, Vertical));
+                }
+                tokens
+            }
+            RecordEventKind::Click { button, count } => {
+                let button = parse_button(button)?;
+                (0..*count).map(|_| Token::Button(button, Click)).collect()
+            }
+            RecordEventKind::Text { text } => vec![Token::Text(text.to_owned())],
+            RecordEventKind::Hotkey { keys } => {
+                let mut tokens = Vec::new();
+                for chord in parse_hotkey(keys)? {
+                    for modifier in chord.modifiers.iter() {
+                        tokens.push(Token::Key(*modifier, Press));
+                    }
+                    tokens.push(Token::Key(chord.key, Click));
+                    for modifier in chord.modifiers.iter().rev() {
+                        tokens.push(Token::Key(*modifier, Release));
+                    }
+                }
+                tokens
+            }
+            // 回放时按录制的坐标输入，不需要查找图片
+            RecordEventKind::Image { .. } | RecordEventKind::Marker { .. } => Vec::new(),
+        };
+        Ok(tokens)
+    }
+}
+
+fn button_name(button: &Button) -> Option<&'static str> {
+    match button {
+        Button::Left => Some("left"),
+        Button::Right => Some("right"),
+        Button::Middle => Some("middle"),
+        _ => None,
+    }
+}
+
+/// 读取 JSONL 录制文件，空行会被忽略
+pub fn read_events(path: impl AsRef<Path>) -> Result<Vec<RecordEvent>> {
+    let reader = BufReader::new(File::open(path.as_ref())?);
+    let mut events = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|error| {
+            anyhow!(t!(
+                "The record event is invalid.",
+                line = index + 1,
+                error = error.to_string()
+            ))
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// 以 JSONL 格式逐行写入录制事件，鼠标坐标保存为相对于 `origin` 的坐标
+pub struct EventWriter {
+    writer: BufWriter<File>,
+    origin: Point,
+}
+
+impl EventWriter {
+    pub fn new(path: impl AsRef<Path>, origin: Point) -> Result<Self> {
+        let writer = BufWriter::new(File::create(path.as_ref())?);
+        Ok(Self { writer, origin })
+    }
+
+    pub fn write(&mut self, event: &RecordEvent) -> Result<()> {
+        let event = event.translated(-self.origin.x, -self.origin.y);
+        writeln!(self.writer, "{}", serde_json::to_string(&event)?)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
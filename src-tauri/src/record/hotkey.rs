@@ -0,0 +1,220 @@
+use crate::input::{Chord, Key, Press, Release, SystemKey, Token, parse_hotkey};
+use anyhow::{Result, anyhow};
+
+/// 录制时通过热键触发的操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordAction {
+    Pause,
+    Resume,
+    Marker,
+}
+
+/// 录制热键，为 `None` 时不启用
+#[derive(Debug, Clone, Default)]
+pub struct RecordHotkeys {
+    pub pause: Option<Chord>,
+    pub resume: Option<Chord>,
+    pub marker: Option<Chord>,
+    /// 只需要从录制中去掉、不触发操作的热键，例如由全局快捷键处理的开始和停止录制
+    pub ignored: Vec<Chord>,
+}
+
+impl RecordHotkeys {
+    pub fn new(
+        pause: &str,
+        resume: &str,
+        marker: &str,
+        ignored: &[impl AsRef<str>],
+    ) -> Result<Self> {
+        let ignored = ignored
+            .iter()
+            .filter_map(|hotkey| Self::parse(hotkey.as_ref()).transpose())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            pause: Self::parse(pause)?,
+            resume: Self::parse(resume)?,
+            marker: Self::parse(marker)?,
+            ignored,
+        })
+    }
+
+    /// 解析单个组合键，空字符串表示不启用
+    pub fn parse(hotkey: &str) -> Result<Option<Chord>> {
+        if hotkey.trim().is_empty() {
+            return Ok(None);
+        }
+        let mut chords = parse_hotkey(hotkey)?;
+        if chords.len() != 1 {
+            return Err(anyhow!(t!(
+                "The recorder hotkey must be a single key combination.",
+                hotkey = hotkey
+            )));
+        }
+        Ok(chords.pop())
+    }
+
+    fn actions(&self) -> [(Option<&Chord>, RecordAction); 3] {
+        [
+            (self.pause.as_ref(), RecordAction::Pause),
+            (self.resume.as_ref(), RecordAction::Resume),
+            (self.marker.as_ref(), RecordAction::Marker),
+        ]
+    }
+}
+
+/// 在录制的输入写入脚本之前过滤热键
+///
+/// 修饰键按下时先暂存，直到确定后续的按键不是热键才写入，因此热键本身（包括修饰键）不会出现在录制中。
+/// 录制开始前就已按下的按键，其松开事件也会被丢弃。
+pub struct HotkeyFilter {
+    hotkeys: RecordHotkeys,
+    /// 录制期间按下且尚未松开的按键
+    held: Vec<SystemKey>,
+    /// 已经写入录制、尚未松开的按键
+    recorded: Vec<SystemKey>,
+    /// 暂存的修饰键
+    pending: Vec<SystemKey>,
+    paused: bool,
+}
+
+impl HotkeyFilter {
+    pub fn new(hotkeys: RecordHotkeys) -> Self {
+        Self {
+            hotkeys,
+            held: Vec::new(),
+            recorded: Vec::new(),
+            pending: Vec::new(),
+            paused: false,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 返回需要写入录制的输入，以及触发的操作
+    pub fn filter(&mut self, tokens: Vec<Token>) -> (Vec<Token>, Option<RecordAction>) {
+        let mut output = Vec::new();
+        let mut action = None;
+        for token in tokens {
+            match token {
+                Token::Key(key, Press) => {
+                    if let Some(triggered) = self.press(key, &mut output) {
+                        action = Some(triggered);
+                    }
+                }
+                Token::Key(key, Release) => self.release(key, &mut output),
+                token => {
+                    if !self.paused {
+                        self.flush(&mut output);
+                        output.push(token);
+                    }
+                }
+            }
+        }
+        (output, action)
+    }
+
+    fn press(&mut self, key: SystemKey, output: &mut Vec<Token>) -> Option<RecordAction> {
+        if !self.held.contains(&key) {
+            self.held.push(key);
+        }
+        if is_modifier(key) {
+            if !self.pending.contains(&key) && !self.recorded.contains(&key) {
+                self.pending.push(key);
+            }
+            return None;
+        }
+        if let Some(action) = self.match_action(key) {
+            self.pending.clear();
+            match action {
+                RecordAction::Pause => self.paused = true,
+                RecordAction::Resume => self.paused = false,
+                _ => {}
+            }
+            return Some(action);
+        }
+        if self.is_ignored(key) || self.paused {
+            self.pending.clear();
+            return None;
+        }
+        self.flush(output);
+        if !self.recorded.contains(&key) {
+            self.recorded.push(key);
+        }
+        output.push(Token::Key(key, Press));
+        None
+    }
+
+    fn release(&mut self, key: SystemKey, output: &mut Vec<Token>) {
+        self.held.retain(|held| *held != key);
+        if self.pending.contains(&key) {
+            // 单独按下又松开的修饰键
+            if self.paused {
+                self.pending.retain(|pending| *pending != key);
+                return;
+            }
+            self.flush(output);
+        }
+        if self.recorded.contains(&key) {
+            self.recorded.retain(|recorded| *recorded != key);
+            output.push(Token::Key(key, Release));
+        }
+    }
+
+    fn flush(&mut self, output: &mut Vec<Token>) {
+        for key in self.pending.drain(..) {
+            self.recorded.push(key);
+            output.push(Token::Key(key, Press));
+        }
+    }
+
+    // 暂停和继续使用同一个热键时，根据当前状态切换
+    fn match_action(&self, key: SystemKey) -> Option<RecordAction> {
+        self.hotkeys
+            .actions()
+            .into_iter()
+            .filter(|(chord, _)| chord.is_some_and(|chord| self.matches(chord, key)))
+            .map(|(_, action)| action)
+            .find(|action| match action {
+                RecordAction::Pause => !self.paused,
+                RecordAction::Resume => self.paused,
+                _ => true,
+            })
+    }
+
+    fn is_ignored(&self, key: SystemKey) -> bool {
+        let mut chords = self.hotkeys.ignored.iter().chain(
+            self.hotkeys
+                .actions()
+                .into_iter()
+                .filter_map(|(chord, _)| chord),
+        );
+        chords.any(|chord| self.matches(chord, key))
+    }
+
+    fn matches(&self, chord: &Chord, key: SystemKey) -> bool {
+        let modifiers: Vec<&SystemKey> = self
+            .held
+            .iter()
+            .filter(|held| is_modifier(**held))
+            .collect();
+        chord.key == key
+            && chord.modifiers.len() == modifiers.len()
+            && chord.modifiers.iter().all(|modifier| {
+                modifiers
+                    .iter()
+                    .any(|held| same_modifier(**held, *modifier))
+            })
+    }
+}
+
+fn is_modifier(key: SystemKey) -> bool {
+    Key::try_from(key).is_ok_and(|key| key.is_modifier())
+}
+
+// 热键中不区分左右的修饰键，按下任意一侧都可以匹配
+fn same_modifier(held: SystemKey, modifier: SystemKey) -> bool {
+    held == modifier
+        || Key::try_from(held).is_ok_and(|held| SystemKey::from(held.generic()) == modifier)
+}
@@ -8,11 +8,11 @@ impl TryFrom<RecordKey> for Key {
     fn try_from(record_key: RecordKey) -> Result<Self, Self::Error> {
         let key = match record_key {
             RecordKey::Alt => Key::Alt,
-            RecordKey::AltGr => Key::Alt,
+            RecordKey::AltGr => Key::AltRight,
             RecordKey::Backspace => Key::Backspace,
             RecordKey::CapsLock => Key::CapsLock,
             RecordKey::ControlLeft => Key::Control,
-            RecordKey::ControlRight | RecordKey::Unknown(62) => Key::Control,
+            RecordKey::ControlRight | RecordKey::Unknown(62) => Key::ControlRight,
             RecordKey::Delete | RecordKey::Unknown(117) => Key::Delete,
             RecordKey::DownArrow => Key::DownArrow,
             RecordKey::End | RecordKey::Unknown(119) => Key::End,
@@ -40,13 +40,13 @@ impl TryFrom<RecordKey> for Key {
             RecordKey::Home | RecordKey::Unknown(115) => Key::Home,
             RecordKey::LeftArrow => Key::LeftArrow,
             RecordKey::MetaLeft => Key::Meta,
-            RecordKey::MetaRight => Key::Meta,
+            RecordKey::MetaRight => Key::MetaRight,
             RecordKey::PageDown | RecordKey::Unknown(121) => Key::PageDown,
             RecordKey::PageUp | RecordKey::Unknown(116) => Key::PageUp,
             RecordKey::Return => Key::Return,
             RecordKey::RightArrow => Key::RightArrow,
             RecordKey::ShiftLeft => Key::Shift,
-            RecordKey::ShiftRight => Key::Shift,
+            RecordKey::ShiftRight => Key::ShiftRight,
             RecordKey::Space => Key::Space,
             RecordKey::Tab => Key::Tab,
             RecordKey::UpArrow => Key::UpArrow,
@@ -1,4 +1,22 @@
+mod chord;
+mod compress;
+mod convert;
 pub mod engine;
+mod event;
+mod hotkey;
+mod region;
+mod replayer;
+mod snapshot;
+mod writer;
+pub use chord::{ChordTracker, RecordInput};
+pub use compress::Compression;
+pub use convert::events_to_script;
 pub use engine::*;
+pub use event::{EventWriter, RecordEvent, RecordEventKind, read_events};
+pub use hotkey::{HotkeyFilter, RecordAction, RecordHotkeys};
 pub use rdev::Event;
+pub use region::RegionFilter;
+pub use replayer::{ReplayOptions, Replayer, replay};
+pub use snapshot::ImageRecorder;
+pub use writer::ScriptWriter;
 mod key;
@@ -0,0 +1,170 @@
+use crate::app::App;
+use crate::input::{Abs, Button, Press, Region, RegionPolicy, Release, SystemKey, Token};
+use crate::types::Point;
+use std::time::{Duration, Instant};
+use tauri::{Manager as _, WebviewWindow};
+
+/// Kiwi 自身的窗口
+const KIWI_WINDOW_LABELS: [&str; 2] = ["main", "monitor"];
+
+/// 鼠标移动时使用缓存的窗口位置，超过该时长才重新获取
+const KIWI_WINDOW_REFRESH_MILLIS: u64 = 500;
+
+/// 录制时按位置过滤输入
+///
+/// * 设置了 `region` 时，只录制区域内的鼠标输入，键盘输入不受影响
+/// * `exclude_self` 为 `true` 时，丢弃针对 Kiwi 自身窗口的点击、滚动，以及 Kiwi 窗口获得焦点时的按键
+///
+/// 输出的坐标仍然是屏幕坐标，保存录制事件时再由 [`EventWriter`](super::EventWriter) 转换为相对于原点的坐标。
+/// 按下事件被丢弃时，对应的松开事件也会被丢弃；拖拽过程中的鼠标移动不受区域限制。
+pub struct RegionFilter {
+    region: Option<Region>,
+    exclude_self: bool,
+    windows: KiwiWindows,
+    position: Option<Point>,
+    /// 已经写入录制、尚未松开的按钮
+    buttons: Vec<Button>,
+    /// 被丢弃、尚未松开的按键
+    dropped_keys: Vec<SystemKey>,
+}
+
+impl RegionFilter {
+    pub fn new(region: Option<Region>, exclude_self: bool) -> Self {
+        Self {
+            region,
+            exclude_self,
+            windows: KiwiWindows::default(),
+            position: None,
+            buttons: Vec::new(),
+            dropped_keys: Vec::new(),
+        }
+    }
+
+    pub fn filter(&mut self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut output = Vec::new();
+        for token in tokens {
+            match token {
+                Token::MoveMouse(x, y, Abs) => {
+                    let point = Point::new(x, y);
+                    self.position = Some(point);
+                    if self.buttons.is_empty() && !self.accepts(&point) {
+                        continue;
+                    }
+                    output.push(token);
+                }
+                Token::Button(button, Press) => {
+                    let point = self.position();
+                    self.windows.refresh();
+                    if self.accepts(&point) {
+                        self.buttons.push(button);
+                        output.push(token);
+                    }
+                }
+                // 录制开始前按下的按钮，例如点击开始录制，其松开事件也会被丢弃
+                Token::Button(button, Release) => {
+                    if let Some(index) = self.buttons.iter().position(|held| *held == button) {
+                        self.buttons.remove(index);
+                        output.push(token);
+                    }
+                }
+                Token::Scroll(..) => {
+                    let point = self.position();
+                    if self.accepts(&point) {
+                        output.push(token);
+                    }
+                }
+                Token::Key(key, Press) if self.exclude_self => {
+                    self.windows.refresh();
+                    if !self.windows.focused {
+                        output.push(token);
+                    } else if !self.dropped_keys.contains(&key) {
+                        self.dropped_keys.push(key);
+                    }
+                }
+                Token::Key(key, Release) if self.dropped_keys.contains(&key) => {
+                    self.dropped_keys.retain(|dropped| *dropped != key);
+                }
+                token => output.push(token),
+            }
+        }
+        output
+    }
+
+    // 还没有收到鼠标移动时，使用当前的鼠标位置
+    fn position(&self) -> Point {
+        self.position
+            .unwrap_or_else(|| App::input().get_mouse_location())
+    }
+
+    fn accepts(&mut self, point: &Point) -> bool {
+        if self.region.is_some_and(|region| !region.contains(point)) {
+            return false;
+        }
+        if self.exclude_self {
+            self.windows.refresh_if_stale();
+            return !self.windows.contains(point);
+        }
+        true
+    }
+}
+
+#[derive(Default)]
+struct KiwiWindows {
+    rects: Vec<Region>,
+    focused: bool,
+    refreshed: Option<Instant>,
+}
+
+impl KiwiWindows {
+    fn refresh(&mut self) {
+        let app_handle = App::get_app_handle();
+        self.rects.clear();
+        self.focused = false;
+        for label in KIWI_WINDOW_LABELS {
+            let Some(window) = app_handle.get_webview_window(label) else {
+                continue;
+            };
+            let visible = window.is_visible().unwrap_or(false);
+            if !visible || window.is_minimized().unwrap_or(false) {
+                continue;
+            }
+            self.focused |= window.is_focused().unwrap_or(false);
+            self.rects.extend(window_rect(&window));
+        }
+        self.refreshed = Some(Instant::now());
+    }
+
+    fn refresh_if_stale(&mut self) {
+        let stale = self.refreshed.is_none_or(|refreshed| {
+            refreshed.elapsed() >= Duration::from_millis(KIWI_WINDOW_REFRESH_MILLIS)
+        });
+        if stale {
+            self.refresh();
+        }
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        self.rects.iter().any(|rect| rect.contains(point))
+    }
+}
+
+// 录制到的坐标在 macOS 上是逻辑坐标，在 Windows 上是物理坐标
+fn window_rect(window: &WebviewWindow) -> Option<Region> {
+    let position = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    let (x, y) = (position.x, position.y);
+    let (width, height) = (size.width as i32, size.height as i32);
+
+    #[cfg(target_os = "macos")]
+    let (x, y, width, height) = {
+        let scale_factor = window.scale_factor().ok()?;
+        let logical = |value: i32| (value as f64 / scale_factor).round() as i32;
+        (logical(x), logical(y), logical(width), logical(height))
+    };
+
+    Some(Region::new(
+        Point::new(x, y),
+        Point::new(x + width - 1, y + height - 1),
+        RegionPolicy::Reject,
+    ))
+}
@@ -0,0 +1,138 @@
+use super::RecordEvent;
+use crate::app::{App, Log};
+use crate::input::{Abs, Token};
+use crate::types::Point;
+use anyhow::{Result, anyhow};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// 两次检查是否停止之间的最长等待时间
+const REPLAY_POLL_MILLIS: u64 = 10;
+
+/// 回放设置
+///
+/// * `speed` - 速度倍数，`2.0` 表示以两倍速回放
+/// * `loops` - 回放次数，`0` 表示一直回放直到停止
+/// * `origin` - 录制时的坐标原点，回放时鼠标坐标加上该点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    pub speed: f64,
+    pub loops: u32,
+    pub origin: Point,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            loops: 1,
+            origin: Point::new(0, 0),
+        }
+    }
+}
+
+/// 按录制时的时间间隔，通过输入引擎回放录制事件
+pub struct Replayer {
+    running: Arc<AtomicBool>,
+}
+
+impl Default for Replayer {
+    fn default() -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl Replayer {
+    /// 在后台线程中回放，结束后调用 `on_finished`，中途停止时会松开所有按键
+    pub fn start<F>(
+        &self,
+        events: Vec<RecordEvent>,
+        options: ReplayOptions,
+        on_finished: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(Result<()>) + Send + 'static,
+    {
+        if options.speed <= 0.0 || !options.speed.is_finite() {
+            return Err(anyhow!(t!(
+                "The replay speed is invalid.",
+                speed = options.speed
+            )));
+        }
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(anyhow!(t!("Replay is running. Please stop it first.")));
+        }
+        let running = Arc::clone(&self.running);
+        thread::spawn(move || {
+            let result = replay(&events, options, &running);
+            if !running.load(Ordering::SeqCst) || result.is_err() {
+                if let Err(error) = App::input().release_all() {
+                    Log::error(error.to_string()).send_to_app_log();
+                }
+            }
+            running.store(false, Ordering::SeqCst);
+            on_finished(result);
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+/// 在当前线程中回放，`running` 变为 `false` 时停止
+pub fn replay(events: &[RecordEvent], options: ReplayOptions, running: &AtomicBool) -> Result<()> {
+    let mut count = 0;
+    while options.loops == 0 || count < options.loops {
+        count += 1;
+        let started = Instant::now();
+        for event in events {
+            let due = Duration::from_secs_f64(event.time as f64 / 1000.0 / options.speed);
+            if !wait_until(started + due, running) {
+                return Ok(());
+            }
+            let tokens: Vec<Token> = event
+                .to_tokens()?
+                .into_iter()
+                .map(|token| match token {
+                    Token::MoveMouse(x, y, Abs) => {
+                        Token::MoveMouse(x + options.origin.x, y + options.origin.y, Abs)
+                    }
+                    token => token,
+                })
+                .collect();
+            App::input().execute_token(&tokens)?;
+        }
+        if events.is_empty() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// 等待到指定时间，期间被停止时返回 false
+fn wait_until(deadline: Instant, running: &AtomicBool) -> bool {
+    loop {
+        if !running.load(Ordering::SeqCst) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(REPLAY_POLL_MILLIS)));
+    }
+}
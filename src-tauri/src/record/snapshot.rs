@@ -0,0 +1,79 @@
+use super::{RecordEvent, RecordEventKind};
+use crate::app::App;
+use crate::extensions::ImageBufferRgbaExt as _;
+use crate::types::{Point, Size};
+use anyhow::Result;
+use fs_extra::dir;
+use std::path::{Path, PathBuf};
+
+/// 录制时在鼠标按下的位置截取模板图片，保存到 `data/images/record_N/`
+pub struct ImageRecorder {
+    dir: PathBuf,
+    name: String,
+    size: u32,
+    threshold: f64,
+    count: u32,
+}
+
+impl ImageRecorder {
+    /// `record_name` 为录制脚本的文件名（不含后缀），例如 `record3`
+    pub fn new(project_path: &Path, record_name: &str, size: u32, threshold: f64) -> Self {
+        let name = record_name.replacen("record", "record_", 1);
+        let dir = project_path.join("data").join("images").join(&name);
+        Self {
+            dir,
+            name,
+            size: size.max(1),
+            threshold,
+            count: 0,
+        }
+    }
+
+    /// 以鼠标位置 `point` 为中心截取模板图片，靠近屏幕边缘时向内移动，保证图片大小不变
+    ///
+    /// 鼠标位置在 macOS 上是逻辑坐标，按截图与显示器大小的比例转换为截图中的像素位置。
+    /// 事件中的 `x`、`y` 仍然是鼠标位置，与前后的鼠标移动使用相同的坐标。
+    /// 截图只包含主显示器，鼠标在其他显示器上时不截取，返回 `None`
+    pub fn capture(&mut self, time: u64, point: &Point) -> Result<Option<RecordEvent>> {
+        let monitor_size = App::with_capturer(|capturer| capturer.get_monitor_size())?;
+        // 主显示器的左上角是坐标原点
+        if point.x < 0
+            || point.y < 0
+            || point.x >= monitor_size.width as i32
+            || point.y >= monitor_size.height as i32
+        {
+            return Ok(None);
+        }
+        let frame = App::get_frame_arc()?;
+        let scale_x = frame.width as f64 / monitor_size.width.max(1) as f64;
+        let scale_y = frame.height as f64 / monitor_size.height.max(1) as f64;
+        let pixel = Point::new(
+            (point.x as f64 * scale_x).round() as i32,
+            (point.y as f64 * scale_y).round() as i32,
+        );
+        let buffer = frame.to_buffer()?;
+        let width = self.size.min(frame.width);
+        let height = self.size.min(frame.height);
+        let left = (pixel.x - width as i32 / 2).clamp(0, (frame.width - width) as i32);
+        let top = (pixel.y - height as i32 / 2).clamp(0, (frame.height - height) as i32);
+        let patch = buffer.crop(Point::new(left, top), Size::new(width, height));
+
+        self.count += 1;
+        dir::create_all(&self.dir, false)?;
+        patch.save(self.dir.join(format!("{}.png", self.count)))?;
+
+        let kind = RecordEventKind::Image {
+            subpath: format!("{}/{}", self.name, self.count),
+            x: point.x,
+            y: point.y,
+            offset_x: pixel.x - left,
+            offset_y: pixel.y - top,
+            screen_width: frame.width,
+            screen_height: frame.height,
+            threshold: self.threshold,
+            scale_x,
+            scale_y,
+        };
+        Ok(Some(RecordEvent::new(time, kind)))
+    }
+}
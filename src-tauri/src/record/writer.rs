@@ -0,0 +1,57 @@
+use super::RecordInput;
+use crate::interpreter::Code;
+use anyhow::Result;
+use std::{
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::Path,
+};
+
+/// 录制时逐行写入脚本，代码由项目语言的 [`Code`] 生成
+///
+/// 每写一行都会立即刷新，写入失败时返回错误，由调用方决定是否停止录制
+pub struct ScriptWriter {
+    writer: BufWriter<File>,
+    code: Box<dyn Code>,
+}
+
+impl ScriptWriter {
+    /// 创建脚本文件并写入开头
+    pub fn new(path: impl AsRef<Path>, code: Box<dyn Code>) -> Result<Self> {
+        let file = File::create(path)?;
+        let mut writer = Self {
+            writer: BufWriter::new(file),
+            code,
+        };
+        let header = writer.code.predefined();
+        writer.write_line(header.trim_end())?;
+        Ok(writer)
+    }
+
+    pub fn write_sleep(&mut self, millis: u64) -> Result<()> {
+        let statement = self.code.sleep(millis);
+        self.write_line(&statement)
+    }
+
+    pub fn write_comment(&mut self, text: &str) -> Result<()> {
+        let statement = self.code.comment(text);
+        self.write_line(&statement)
+    }
+
+    pub fn write_inputs(&mut self, inputs: &[RecordInput]) -> Result<()> {
+        for input in inputs {
+            let statement = match input {
+                RecordInput::Token(token) => self.code.token(token)?,
+                RecordInput::Hotkey(chord) => self.code.hotkey(chord)?,
+            };
+            self.write_line(&statement)?;
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        writeln!(self.writer, "{}", line)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+mod runner;
+mod runs;
+mod watchdog;
+
+pub use runner::*;
+pub use runs::*;
+pub use watchdog::*;
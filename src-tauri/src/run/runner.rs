@@ -0,0 +1,243 @@
+use super::{RunId, RunStatus, RunStream, Watchdog};
+use crate::{
+    app::{App, Log},
+    commands::frontend::utils::emit,
+    interpreter::{Interpreter, RunOptions},
+    websocket,
+};
+use anyhow::{Result, anyhow};
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::AppHandle;
+
+/// 等待重新运行时检查是否停止的间隔
+const RESTART_POLL_MILLIS: u64 = 100;
+/// 等待后台截图第一帧时的检查间隔
+const FRAME_POLL_MILLIS: u64 = 10;
+/// 等待后台截图第一帧的最长时间
+const FRAME_TIMEOUT_MILLIS: u64 = 10_000;
+
+/// 运行脚本的请求
+///
+/// * `path` - 脚本路径
+/// * `profile` - 生成 `options` 时使用的运行配置，只用于显示
+/// * `options` - 参数、环境变量和工作目录
+/// * `watchdog` - 超时停止和出错后重新运行的设置
+#[derive(Debug, Clone)]
+pub struct RunRequest {
+    pub path: String,
+    pub profile: Option<String>,
+    pub options: RunOptions,
+    pub watchdog: Watchdog,
+}
+
+/// 在后台线程中运行脚本，返回运行编号
+///
+/// 同时运行的脚本共用后台截图和输入设置。第一个脚本开始时从项目配置恢复输入区域和拟人化设置，
+/// 同时运行多个脚本时脚本不能修改输入模式、区域、拟人化和演练模式；
+/// 最后一个脚本结束时关闭紧急停止、松开脚本留下的按键和鼠标按钮，并发送 `run:status` 为 `stopped`。
+/// 每次运行的状态通过 `run:update` 发送，输出通过 `run:log` 发送。
+/// 脚本出错或被运行监控停止时，按 `watchdog` 的设置等待后使用同一个运行编号重新运行。
+/// 等不到后台截图的第一帧或截图被强制停止时，运行记为失败。
+pub fn start(request: RunRequest) -> Result<RunId> {
+    if App::with_recorder(|recorder| recorder.is_running()) {
+        return Err(anyhow!(t!("Recorder is running. Please stop it first.")));
+    }
+    let interpreter = App::try_with_project(|project| project.interpreter.new_instance())?;
+    let capture = App::with_capturer(|capturer| capturer.acquire())?;
+
+    let RunRequest {
+        path,
+        profile,
+        mut options,
+        watchdog,
+    } = request;
+    let (id, active) = App::with_runs(|runs| runs.add(&path, profile, interpreter.clone()));
+    options.run_id = Some(id);
+    let app_handle = App::get_app_handle();
+    emit(
+        &app_handle,
+        "run:update",
+        App::with_runs(|runs| runs.get(id)),
+    );
+
+    thread::spawn(move || {
+        'run: {
+            if let Err(error) = wait_for_frame(id, capture) {
+                if !App::with_runs(|runs| runs.is_stop_requested(id)) {
+                    Log::error(error.to_string()).send_to_app_log();
+                }
+                let info = App::with_runs(|runs| runs.finish(id, None));
+                emit(&app_handle, "run:update", info);
+                break 'run;
+            }
+
+            if active == 0 {
+                // 每次开始运行都从项目配置恢复输入区域和拟人化设置，避免上一次运行中的修改遗留下来，
+                // 设置了种子时随机序列也从头开始
+                if let Ok(Err(error)) = App::try_with_project(|project| {
+                    App::input().set_region(project.config.input.region)?;
+                    App::input().set_humanize(project.config.input.humanize)
+                }) {
+                    Log::error(error.to_string()).send_to_app_log();
+                }
+            }
+
+            let failsafe = App::with_config(|config| config.failsafe.clone());
+            if failsafe.enabled && !App::with_failsafe(|engine| engine.is_running()) {
+                if let Err(error) = App::with_failsafe(|engine| engine.start(&failsafe)) {
+                    Log::error(error.to_string()).send_to_app_log();
+                }
+            }
+
+            let mut interpreter = interpreter;
+            let mut attempt = 1;
+            loop {
+                watchdog.watch(id, attempt);
+                run_once(&app_handle, id, &interpreter, &path, &options);
+
+                let Some(info) = App::with_runs(|runs| runs.get(id)) else {
+                    break;
+                };
+                if info.status != RunStatus::Failed || attempt > watchdog.max_restarts {
+                    break;
+                }
+                let backoff = watchdog.backoff(attempt);
+                let msg = t!(
+                    "The script will restart.",
+                    id = id,
+                    attempt = attempt,
+                    max = watchdog.max_restarts,
+                    seconds = backoff.as_secs()
+                );
+                Log::warn(msg.to_string()).send_to_app_log();
+                emit(
+                    &app_handle,
+                    "run:update",
+                    App::with_runs(|runs| runs.set_restarting(id)),
+                );
+                if !wait_for_restart(id, backoff) {
+                    let info = App::with_runs(|runs| runs.finish(id, None));
+                    emit(&app_handle, "run:update", info);
+                    break;
+                }
+                interpreter = interpreter.new_instance();
+                let info = App::with_runs(|runs| runs.restart(id, interpreter.clone()));
+                emit(&app_handle, "run:update", info);
+                attempt += 1;
+            }
+        }
+
+        websocket::forget_calls(id);
+        App::with_capturer(|capturer| capturer.release(capture));
+        if App::with_runs(|runs| runs.active_count()) == 0 {
+            App::with_failsafe(|engine| engine.stop());
+            // 脚本被停止或出错时可能没有松开按键，只在没有其他脚本运行时松开，避免影响它们的输入
+            if let Err(error) = App::input().release_all() {
+                Log::error(error.to_string()).send_to_app_log();
+            }
+            emit(&app_handle, "run:status", "stopped");
+        }
+    });
+    Ok(id)
+}
+
+// 在当前线程中运行一次，结束后运行状态已经记录
+fn run_once(
+    app_handle: &Arc<AppHandle>,
+    id: RunId,
+    interpreter: &Interpreter,
+    path: &str,
+    options: &RunOptions,
+) {
+    let app_handle_spawned = Arc::clone(app_handle);
+    let app_handle_stdout = Arc::clone(app_handle);
+    let app_handle_stderr = Arc::clone(app_handle);
+    let app_handle_exit = Arc::clone(app_handle);
+    let interpreter_spawned = interpreter.clone();
+    let on_spawned = move |pid| {
+        let info = App::with_runs(|runs| runs.set_running(id, pid));
+        // 启动之前请求过停止
+        if App::with_runs(|runs| runs.is_stop_requested(id)) {
+            let grace = App::with_config(|config| config.stop.grace());
+            interpreter_spawned.stop(grace);
+        }
+        let msg = t!("The script is now running.", id = id, pid = pid);
+        Log::success(msg.to_string()).send_to_app_log();
+        emit(&app_handle_spawned, "run:update", info);
+        emit(&app_handle_spawned, "run:status", "running");
+    };
+    let on_stdout = move |line: String| {
+        Log::info(format!("[{}] {}", id, line)).send_to_app_log();
+        let log_line = App::with_runs(|runs| runs.push_log(id, RunStream::Stdout, line));
+        emit(&app_handle_stdout, "run:log", log_line);
+    };
+    let on_stderr = move |line: String| {
+        Log::error(format!("[{}] {}", id, line)).send_to_app_log();
+        let log_line = App::with_runs(|runs| runs.push_log(id, RunStream::Stderr, line));
+        emit(&app_handle_stderr, "run:log", log_line);
+    };
+    let on_exit = move |_pid, exit| {
+        let info = App::with_runs(|runs| runs.finish(id, Some(exit)));
+        let msg = t!("The script has completed.", id = id, status = exit);
+        Log::success(msg.to_string()).send_to_app_log();
+        emit(&app_handle_exit, "run:update", info);
+    };
+
+    let port = App::with_config(|config| config.app.websocket_port);
+    // 运行期间不持有项目的锁，在 Kiwi 进程内运行的脚本也会读取项目
+    if let Err(error) = interpreter.run(
+        path, port, options, on_spawned, on_stdout, on_stderr, on_exit,
+    ) {
+        Log::error(error.to_string()).send_to_app_log();
+        let info = App::with_runs(|runs| runs.finish(id, None));
+        emit(app_handle, "run:update", info);
+    }
+}
+
+// 等待重新运行，期间被停止时返回 false
+fn wait_for_restart(id: RunId, backoff: Duration) -> bool {
+    let deadline = Instant::now() + backoff;
+    loop {
+        if App::with_runs(|runs| runs.is_stop_requested(id)) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(RESTART_POLL_MILLIS)));
+    }
+}
+
+// 等待后台截图的第一帧，期间被停止、截图被强制停止或超时时返回错误
+fn wait_for_frame(id: RunId, capture: u64) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_millis(FRAME_TIMEOUT_MILLIS);
+    loop {
+        if App::get_frame_arc().is_ok() {
+            return Ok(());
+        }
+        if App::with_runs(|runs| runs.is_stop_requested(id)) {
+            return Err(anyhow!(t!(
+                "The script was stopped before it started.",
+                id = id
+            )));
+        }
+        if !App::with_capturer(|capturer| capturer.is_current(capture)) {
+            return Err(anyhow!(t!(
+                "Screen capture was stopped before the script started.",
+                id = id
+            )));
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!(t!(
+                "Timed out waiting for the first screen capture frame.",
+                id = id
+            )));
+        }
+        thread::sleep(Duration::from_millis(FRAME_POLL_MILLIS));
+    }
+}
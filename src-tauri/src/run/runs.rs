@@ -0,0 +1,316 @@
+use crate::interpreter::{Interpreter, ScriptExit};
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// 每次运行的编号，从 1 开始递增
+pub type RunId = u32;
+
+/// 每次运行最多保留的输出行数，超出时丢弃最早的行
+const MAX_LOG_LINES: usize = 1000;
+/// 最多保留的已结束运行数量，超出时丢弃最早结束的运行
+const MAX_FINISHED_RUNS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Starting,
+    Running,
+    /// 出错后等待重新运行
+    Restarting,
+    Finished,
+    Failed,
+    Stopped,
+}
+
+impl RunStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            RunStatus::Starting | RunStatus::Running | RunStatus::Restarting
+        )
+    }
+}
+
+/// 运行的信息，通过 `run:update` 事件发送给前端
+///
+/// * `attempt` - 第几次运行，重新运行时加 1，从 1 开始
+/// * `exit` - 结束时的状态，例如进程的退出码
+/// * `reason` - 被运行监控停止的原因
+/// * `started_at`、`ended_at` - 自 UNIX 纪元起的秒数，重新运行时重新记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInfo {
+    pub id: RunId,
+    pub path: String,
+    pub profile: Option<String>,
+    pub pid: u32,
+    pub status: RunStatus,
+    pub attempt: u32,
+    pub exit: Option<String>,
+    pub reason: Option<String>,
+    pub started_at: f64,
+    pub ended_at: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStream {
+    Stdout,
+    Stderr,
+}
+
+/// 脚本输出的一行，通过 `run:log` 事件发送给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunLogLine {
+    pub run_id: RunId,
+    pub stream: RunStream,
+    pub line: String,
+    pub time: f64,
+}
+
+struct Run {
+    info: RunInfo,
+    interpreter: Interpreter,
+    log: VecDeque<RunLogLine>,
+    stop_requested: bool,
+    /// 被运行监控停止
+    expired: bool,
+}
+
+/// 正在运行和最近结束的脚本
+///
+/// 每次运行使用独立的解释器实例，可以分别停止，输出分别保存
+pub struct Runs {
+    next_id: AtomicU32,
+    runs: Mutex<BTreeMap<RunId, Run>>,
+}
+
+impl Default for Runs {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU32::new(1),
+            runs: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl Runs {
+    /// 登记新的运行，返回运行编号和登记前正在运行的数量
+    pub fn add(
+        &self,
+        path: impl Into<String>,
+        profile: Option<String>,
+        interpreter: Interpreter,
+    ) -> (RunId, usize) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut runs = self.runs.lock().unwrap();
+        let active = runs
+            .values()
+            .filter(|run| run.info.status.is_active())
+            .count();
+        let info = RunInfo {
+            id,
+            path: path.into(),
+            profile,
+            pid: 0,
+            status: RunStatus::Starting,
+            attempt: 1,
+            exit: None,
+            reason: None,
+            started_at: now(),
+            ended_at: None,
+        };
+        runs.insert(
+            id,
+            Run {
+                info,
+                interpreter,
+                log: VecDeque::new(),
+                stop_requested: false,
+                expired: false,
+            },
+        );
+        prune_finished(&mut runs);
+        (id, active)
+    }
+
+    pub fn set_running(&self, id: RunId, pid: u32) -> Option<RunInfo> {
+        self.update(id, |run| {
+            run.info.pid = pid;
+            run.info.status = RunStatus::Running;
+        })
+    }
+
+    /// 记录运行结束
+    ///
+    /// 请求过停止的运行记为 `Stopped`，被运行监控停止的记为 `Failed`，
+    /// 外部进程按退出码区分 `Finished` 和 `Failed`
+    pub fn finish(&self, id: RunId, exit: Option<ScriptExit>) -> Option<RunInfo> {
+        self.update(id, |run| {
+            run.info.status = match exit {
+                _ if run.stop_requested => RunStatus::Stopped,
+                _ if run.expired => RunStatus::Failed,
+                Some(ScriptExit::Process(status)) if status.success() => RunStatus::Finished,
+                Some(ScriptExit::Finished) => RunStatus::Finished,
+                Some(ScriptExit::Stopped) => RunStatus::Stopped,
+                _ => RunStatus::Failed,
+            };
+            run.info.exit = exit.map(|exit| exit.to_string());
+            run.info.ended_at = Some(now());
+        })
+    }
+
+    pub fn set_restarting(&self, id: RunId) -> Option<RunInfo> {
+        self.update(id, |run| run.info.status = RunStatus::Restarting)
+    }
+
+    /// 使用新的解释器实例重新运行，输出记录保留
+    pub fn restart(&self, id: RunId, interpreter: Interpreter) -> Option<RunInfo> {
+        self.update(id, |run| {
+            run.interpreter = interpreter;
+            run.expired = false;
+            run.info.attempt += 1;
+            run.info.pid = 0;
+            run.info.status = RunStatus::Starting;
+            run.info.exit = None;
+            run.info.reason = None;
+            run.info.started_at = now();
+            run.info.ended_at = None;
+        })
+    }
+
+    pub fn push_log(&self, id: RunId, stream: RunStream, line: String) -> RunLogLine {
+        let log_line = RunLogLine {
+            run_id: id,
+            stream,
+            line,
+            time: now(),
+        };
+        let mut runs = self.runs.lock().unwrap();
+        if let Some(run) = runs.get_mut(&id) {
+            if run.log.len() >= MAX_LOG_LINES {
+                run.log.pop_front();
+            }
+            run.log.push_back(log_line.clone());
+        }
+        log_line
+    }
+
+    pub fn is_stop_requested(&self, id: RunId) -> bool {
+        let runs = self.runs.lock().unwrap();
+        runs.get(&id).is_some_and(|run| run.stop_requested)
+    }
+
+    pub fn get(&self, id: RunId) -> Option<RunInfo> {
+        let runs = self.runs.lock().unwrap();
+        runs.get(&id).map(|run| run.info.clone())
+    }
+
+    pub fn list(&self) -> Vec<RunInfo> {
+        let runs = self.runs.lock().unwrap();
+        runs.values().map(|run| run.info.clone()).collect()
+    }
+
+    pub fn get_log(&self, id: RunId) -> Result<Vec<RunLogLine>> {
+        let runs = self.runs.lock().unwrap();
+        let run = runs
+            .get(&id)
+            .ok_or_else(|| anyhow!(t!("The run does not exist.", id = id)))?;
+        Ok(run.log.iter().cloned().collect())
+    }
+
+    pub fn active_count(&self) -> usize {
+        let runs = self.runs.lock().unwrap();
+        runs.values()
+            .filter(|run| run.info.status.is_active())
+            .count()
+    }
+
+    /// 停止指定的运行，运行已经结束时返回 `false`
+    ///
+    /// 外部解释器在 `grace` 内没有退出时强制结束。脚本还没有启动时只记录停止请求，启动后立即停止
+    pub fn stop(&self, id: RunId, grace: Duration) -> Result<bool> {
+        let interpreter = {
+            let mut runs = self.runs.lock().unwrap();
+            let run = runs
+                .get_mut(&id)
+                .ok_or_else(|| anyhow!(t!("The run does not exist.", id = id)))?;
+            if !run.info.status.is_active() {
+                return Ok(false);
+            }
+            run.stop_requested = true;
+            run.interpreter.clone()
+        };
+        // 停止外部进程时会等待 taskkill，不持有锁
+        interpreter.stop(grace);
+        Ok(true)
+    }
+
+    /// 运行监控停止运行，与 [`Runs::stop`] 不同，之后可以按设置重新运行
+    pub fn expire(&self, id: RunId, reason: String, grace: Duration) -> Result<bool> {
+        let interpreter = {
+            let mut runs = self.runs.lock().unwrap();
+            let run = runs
+                .get_mut(&id)
+                .ok_or_else(|| anyhow!(t!("The run does not exist.", id = id)))?;
+            if !run.info.status.is_active() {
+                return Ok(false);
+            }
+            run.expired = true;
+            run.info.reason = Some(reason);
+            run.interpreter.clone()
+        };
+        interpreter.stop(grace);
+        Ok(true)
+    }
+
+    /// 停止所有运行，返回请求停止的数量
+    pub fn stop_all(&self, grace: Duration) -> usize {
+        let ids: Vec<RunId> = {
+            let runs = self.runs.lock().unwrap();
+            runs.values()
+                .filter(|run| run.info.status.is_active())
+                .map(|run| run.info.id)
+                .collect()
+        };
+        ids.iter()
+            .filter(|id| self.stop(**id, grace).is_ok())
+            .count()
+    }
+
+    fn update(&self, id: RunId, f: impl FnOnce(&mut Run)) -> Option<RunInfo> {
+        let mut runs = self.runs.lock().unwrap();
+        runs.get_mut(&id).map(|run| {
+            f(run);
+            run.info.clone()
+        })
+    }
+}
+
+fn prune_finished(runs: &mut BTreeMap<RunId, Run>) {
+    let finished: Vec<RunId> = runs
+        .values()
+        .filter(|run| !run.info.status.is_active())
+        .map(|run| run.info.id)
+        .collect();
+    let excess = finished.len().saturating_sub(MAX_FINISHED_RUNS);
+    for id in finished.into_iter().take(excess) {
+        runs.remove(&id);
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64())
+        .unwrap_or_default()
+}
@@ -0,0 +1,113 @@
+use super::RunId;
+use crate::{
+    app::{App, Log},
+    websocket,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// 检查运行是否超时的间隔
+const WATCH_POLL_MILLIS: u64 = 500;
+
+/// 运行监控，适合无人值守时长时间运行的脚本
+///
+/// * `max_runtime_secs` - 每次运行的最长时间（秒），超时后停止，`0` 表示不限制
+/// * `inactivity_secs` - 超过这么多秒没有调用 Kiwi 的方法时停止，`0` 表示不限制。
+///   同时运行多个脚本时，只算本次运行自己的调用
+/// * `max_restarts` - 脚本出错、崩溃或被监控停止后最多重新运行的次数，`0` 表示不重新运行
+/// * `backoff_secs` - 第一次重新运行前等待的时间（秒），之后每次加倍
+/// * `max_backoff_secs` - 重新运行前等待时间的上限（秒）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Watchdog {
+    pub max_runtime_secs: u64,
+    pub inactivity_secs: u64,
+    pub max_restarts: u32,
+    pub backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self {
+            max_runtime_secs: 0,
+            inactivity_secs: 0,
+            max_restarts: 0,
+            backoff_secs: 5,
+            max_backoff_secs: 300,
+        }
+    }
+}
+
+impl Watchdog {
+    /// 第 `restart` 次重新运行前等待的时间，从 1 开始
+    pub fn backoff(&self, restart: u32) -> Duration {
+        let factor = 2u64.saturating_pow(restart.saturating_sub(1));
+        let secs = self.backoff_secs.saturating_mul(factor);
+        Duration::from_secs(secs.min(self.max_backoff_secs))
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_runtime_secs > 0 || self.inactivity_secs > 0
+    }
+
+    /// 在后台线程中监控第 `attempt` 次运行，超时后停止，运行结束或重新运行后退出
+    pub(super) fn watch(&self, id: RunId, attempt: u32) {
+        if !self.is_enabled() {
+            return;
+        }
+        let watchdog = *self;
+        thread::spawn(move || {
+            let started = Instant::now();
+            loop {
+                thread::sleep(Duration::from_millis(WATCH_POLL_MILLIS));
+                let Some(info) = App::with_runs(|runs| runs.get(id)) else {
+                    return;
+                };
+                if info.attempt != attempt || !info.status.is_active() {
+                    return;
+                }
+                let Some(reason) = watchdog.check(id, started) else {
+                    continue;
+                };
+                Log::warn(reason.clone()).send_to_app_log();
+                let grace = App::with_config(|config| config.stop.grace());
+                let _ = App::with_runs(|runs| runs.expire(id, reason, grace));
+                return;
+            }
+        });
+    }
+
+    // 返回需要停止的原因
+    fn check(&self, id: RunId, started: Instant) -> Option<String> {
+        let elapsed = started.elapsed();
+        if self.max_runtime_secs > 0 && elapsed >= Duration::from_secs(self.max_runtime_secs) {
+            return Some(
+                t!(
+                    "The script has exceeded the maximum runtime.",
+                    seconds = self.max_runtime_secs
+                )
+                .to_string(),
+            );
+        }
+        if self.inactivity_secs > 0 {
+            // 本次运行开始之前的调用不算
+            let last_active = websocket::last_call(id)
+                .filter(|last_call| *last_call > started)
+                .unwrap_or(started);
+            if last_active.elapsed() >= Duration::from_secs(self.inactivity_secs) {
+                return Some(
+                    t!(
+                        "The script has been inactive for too long.",
+                        seconds = self.inactivity_secs
+                    )
+                    .to_string(),
+                );
+            }
+        }
+        None
+    }
+}
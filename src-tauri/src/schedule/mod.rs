@@ -0,0 +1,5 @@
+mod schedule;
+mod scheduler;
+
+pub use schedule::*;
+pub use scheduler::*;
@@ -0,0 +1,316 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, str::FromStr};
+
+/// 保存在项目配置中的定时运行，例如：
+///
+/// ```toml
+/// [[schedules]]
+/// name = "nightly"
+/// path = "main.py"
+/// profile = "nightly"
+/// trigger = "cron"
+/// cron = "0 3 * * *"
+/// ```
+///
+/// * `name` - 名称，在项目中唯一
+/// * `path` - 脚本路径，与运行脚本时相同
+/// * `profile` - 运行配置，未设置时使用项目的默认运行配置
+/// * `enabled` - 关闭后不再按时间触发，仍然可以手动运行
+/// * `repeat` - 每次触发时依次运行的次数，上一次结束后才开始下一次
+/// * `repeat_delay_secs` - 依次运行时两次之间等待的时间（秒）
+/// * `trigger` - 触发方式
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Schedule {
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_repeat")]
+    pub repeat: u32,
+    #[serde(default)]
+    pub repeat_delay_secs: u64,
+    #[serde(flatten)]
+    pub trigger: Trigger,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_repeat() -> u32 {
+    1
+}
+
+/// 定时运行的触发方式
+///
+/// * `at` - 在本地时间运行一次，例如 `at = "2026-01-01T08:00:00"`，已经过去的时间不会补运行
+/// * `cron` - 按 cron 表达式运行，支持 5 个字段（分 时 日 月 周）或以秒开头的 6 个字段，
+///   星期与 crontab 相同，`0` 和 `7` 都是星期日，也可以使用英文缩写，例如 `MON-FRI`
+/// * `interval` - 从打开项目或上一次触发起每隔 `interval_secs` 秒运行
+/// * `manual` - 只手动运行，用于重复运行多次
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "trigger", rename_all = "snake_case")]
+pub enum Trigger {
+    At { at: NaiveDateTime },
+    Cron { cron: String },
+    Interval { interval_secs: u64 },
+    Manual,
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!(t!("The schedule name cannot be empty.")));
+        }
+        if self.path.trim().is_empty() {
+            return Err(anyhow!(t!(
+                "The schedule has no script.",
+                name = &self.name
+            )));
+        }
+        if self.repeat == 0 {
+            return Err(anyhow!(t!(
+                "The schedule must run at least once.",
+                name = &self.name
+            )));
+        }
+        match &self.trigger {
+            Trigger::Cron { cron } => {
+                parse_cron(cron)?;
+            }
+            Trigger::Interval { interval_secs } if *interval_secs == 0 => {
+                return Err(anyhow!(t!(
+                    "The schedule interval must be greater than zero.",
+                    name = &self.name
+                )));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// `after` 之后下一次触发的时间，关闭、已经过去或只手动运行时返回 `None`
+    pub fn next_after(&self, after: DateTime<Local>) -> Result<Option<DateTime<Local>>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        let next = match &self.trigger {
+            Trigger::At { at } => Local
+                .from_local_datetime(at)
+                .earliest()
+                .filter(|at| *at > after),
+            Trigger::Cron { cron } => parse_cron(cron)?.after(&after).next(),
+            Trigger::Interval { interval_secs } => i64::try_from(*interval_secs)
+                .ok()
+                .and_then(TimeDelta::try_seconds)
+                .and_then(|interval| after.checked_add_signed(interval)),
+            Trigger::Manual => None,
+        };
+        Ok(next)
+    }
+}
+
+/// cron 表达式中星期所在的字段，从秒开始数
+const DAY_OF_WEEK_FIELD: usize = 5;
+
+// 5 个字段的表达式在开头补上秒，星期从 crontab 的编号转换为 cron 库的编号
+fn parse_cron(expression: &str) -> Result<cron::Schedule> {
+    let mut fields: Vec<String> = expression.split_whitespace().map(String::from).collect();
+    if fields.len() == 5 {
+        fields.insert(0, "0".to_string());
+    }
+    if let Some(field) = fields.get_mut(DAY_OF_WEEK_FIELD) {
+        *field = convert_day_of_week(field);
+    }
+    cron::Schedule::from_str(&fields.join(" ")).map_err(|e| {
+        anyhow!(t!(
+            "Invalid cron expression.",
+            expression = expression.trim(),
+            error = e.to_string()
+        ))
+    })
+}
+
+// crontab 中 0 到 6 为星期日到星期六，7 也是星期日；cron 库中 1 到 7 为星期日到星期六。
+// 数字和数字范围展开为 cron 库的编号，`*`、英文缩写和无效的部分保持不变，由 cron 库处理
+fn convert_day_of_week(field: &str) -> String {
+    field
+        .split(',')
+        .map(|item| convert_day_of_week_item(item).unwrap_or_else(|| item.to_string()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn convert_day_of_week_item(item: &str) -> Option<String> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => (range, step.parse::<usize>().ok()?),
+        None => (item, 1),
+    };
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.parse::<u32>().ok()?, end.parse::<u32>().ok()?),
+        // `1/2` 表示从星期一开始到一周结束
+        None if item.contains('/') => (range.parse::<u32>().ok()?, 7),
+        None => {
+            let day = range.parse::<u32>().ok()?;
+            (day, day)
+        }
+    };
+    if end > 7 || start > end || step == 0 {
+        return None;
+    }
+    let days: BTreeSet<u32> = (start..=end).step_by(step).map(|day| day % 7 + 1).collect();
+    let days: Vec<String> = days.iter().map(u32::to_string).collect();
+    Some(days.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike, Weekday};
+
+    fn schedule(trigger: Trigger) -> Schedule {
+        Schedule {
+            name: "test".to_string(),
+            path: "main.py".to_string(),
+            profile: None,
+            enabled: true,
+            repeat: 1,
+            repeat_delay_secs: 0,
+            trigger,
+        }
+    }
+
+    fn cron(expression: &str) -> Schedule {
+        schedule(Trigger::Cron {
+            cron: expression.to_string(),
+        })
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .single()
+            .unwrap()
+    }
+
+    // 2026-01-04 是星期日
+    fn sunday_noon() -> DateTime<Local> {
+        local(2026, 1, 4, 12, 0)
+    }
+
+    fn weekdays(expression: &str, count: usize) -> Vec<Weekday> {
+        parse_cron(expression)
+            .unwrap()
+            .after(&sunday_noon())
+            .take(count)
+            .map(|time| time.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn parse_cron_pads_seconds() {
+        let next = cron("30 9 * * *")
+            .next_after(sunday_noon())
+            .unwrap()
+            .unwrap();
+        assert_eq!(next, local(2026, 1, 5, 9, 30));
+        assert_eq!(next.second(), 0);
+    }
+
+    #[test]
+    fn parse_cron_accepts_seconds() {
+        let next = cron("15 30 9 * * *")
+            .next_after(sunday_noon())
+            .unwrap()
+            .unwrap();
+        assert_eq!(next, local(2026, 1, 5, 9, 30) + TimeDelta::seconds(15));
+    }
+
+    #[test]
+    fn parse_cron_uses_crontab_day_numbers() {
+        assert_eq!(weekdays("0 9 * * 1", 1), [Weekday::Mon]);
+        assert_eq!(weekdays("0 9 * * 0", 1), [Weekday::Sun]);
+        assert_eq!(weekdays("0 9 * * 7", 1), [Weekday::Sun]);
+        assert_eq!(weekdays("0 9 * * 6", 1), [Weekday::Sat]);
+    }
+
+    #[test]
+    fn parse_cron_converts_day_ranges_and_lists() {
+        assert_eq!(
+            weekdays("0 9 * * 1-5", 5),
+            [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri
+            ]
+        );
+        assert_eq!(
+            weekdays("0 9 * * 5-7", 3),
+            [Weekday::Fri, Weekday::Sat, Weekday::Sun]
+        );
+        assert_eq!(
+            weekdays("0 9 * * 1,3", 3),
+            [Weekday::Mon, Weekday::Wed, Weekday::Mon]
+        );
+        assert_eq!(
+            weekdays("0 9 * * 1-5/2", 3),
+            [Weekday::Mon, Weekday::Wed, Weekday::Fri]
+        );
+        assert_eq!(
+            weekdays("0 9 * * */3", 3),
+            [Weekday::Wed, Weekday::Sat, Weekday::Sun]
+        );
+    }
+
+    #[test]
+    fn parse_cron_keeps_day_names() {
+        assert_eq!(weekdays("0 9 * * MON-FRI", 5), weekdays("0 9 * * 1-5", 5));
+        assert_eq!(weekdays("0 9 * * SUN", 1), [Weekday::Sun]);
+    }
+
+    #[test]
+    fn parse_cron_rejects_invalid_expressions() {
+        assert!(parse_cron("0 9 * *").is_err());
+        assert!(parse_cron("0 25 * * *").is_err());
+        assert!(parse_cron("0 9 * * 8").is_err());
+        assert!(cron("not a cron").validate().is_err());
+    }
+
+    #[test]
+    fn next_after_at() {
+        let at = local(2026, 1, 5, 8, 0);
+        let trigger = Trigger::At {
+            at: at.naive_local(),
+        };
+        assert_eq!(
+            schedule(trigger.clone()).next_after(sunday_noon()).unwrap(),
+            Some(at)
+        );
+        assert_eq!(schedule(trigger).next_after(at).unwrap(), None);
+    }
+
+    #[test]
+    fn next_after_interval() {
+        let next = schedule(Trigger::Interval { interval_secs: 90 })
+            .next_after(sunday_noon())
+            .unwrap();
+        assert_eq!(next, Some(sunday_noon() + TimeDelta::seconds(90)));
+    }
+
+    #[test]
+    fn next_after_disabled_or_manual() {
+        let mut disabled = cron("0 9 * * *");
+        disabled.enabled = false;
+        assert_eq!(disabled.next_after(sunday_noon()).unwrap(), None);
+        assert_eq!(
+            schedule(Trigger::Manual).next_after(sunday_noon()).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use super::Schedule;
+use crate::{
+    app::{App, Log},
+    commands::frontend::utils::emit,
+    run::{self, RunId, RunRequest, RunStatus},
+};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex, Once,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+/// 检查是否到了触发时间的间隔
+const TICK_MILLIS: u64 = 1000;
+/// 依次运行时检查上一次是否结束的间隔
+const WAIT_POLL_MILLIS: u64 = 500;
+
+/// 定时运行的状态，通过 `schedule:update` 事件发送给前端
+///
+/// * `next_run_at`、`last_run_at` - 自 UNIX 纪元起的秒数
+/// * `run_id` - 正在进行的运行
+/// * `iteration` - 依次运行时正在进行第几次，没有运行时为 `0`
+/// * `error` - 配置无效的原因，无效的定时运行不会触发
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleInfo {
+    pub schedule: Schedule,
+    pub next_run_at: Option<f64>,
+    pub last_run_at: Option<f64>,
+    pub run_id: Option<RunId>,
+    pub iteration: u32,
+    pub error: Option<String>,
+}
+
+struct Entry {
+    schedule: Schedule,
+    next: Option<DateTime<Local>>,
+    last: Option<DateTime<Local>>,
+    run_id: Option<RunId>,
+    iteration: u32,
+    active: bool,
+    error: Option<String>,
+    // 修改后重新创建的定时运行使用新的值，旧的依次运行不再继续
+    generation: u64,
+}
+
+impl Entry {
+    fn new(schedule: Schedule, now: DateTime<Local>, generation: u64) -> Self {
+        let next = schedule.validate().and_then(|_| schedule.next_after(now));
+        let (next, error) = match next {
+            Ok(next) => (next, None),
+            Err(error) => {
+                Log::error(error.to_string()).send_to_app_log();
+                (None, Some(error.to_string()))
+            }
+        };
+        Self {
+            schedule,
+            next,
+            last: None,
+            run_id: None,
+            iteration: 0,
+            active: false,
+            error,
+            generation,
+        }
+    }
+
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo {
+            schedule: self.schedule.clone(),
+            next_run_at: self.next.map(timestamp),
+            last_run_at: self.last.map(timestamp),
+            run_id: self.run_id,
+            iteration: self.iteration,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// 按项目配置中的定时运行启动脚本
+///
+/// 打开项目时载入，之后每秒检查一次是否到了触发时间。每次触发按 `repeat` 依次运行，
+/// 运行的方式与手动运行相同，包括运行配置和运行监控。上一次触发还没有结束时跳过本次触发；
+/// Kiwi 没有打开期间错过的触发不会补运行。
+pub struct Scheduler {
+    entries: Mutex<BTreeMap<String, Entry>>,
+    generation: AtomicU64,
+    ticker: Once,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(BTreeMap::new()),
+            generation: AtomicU64::new(0),
+            ticker: Once::new(),
+        }
+    }
+}
+
+impl Scheduler {
+    /// 载入项目的定时运行，替换之前载入的，之前正在进行的依次运行在当前这次结束后不再继续
+    pub fn load(&self, schedules: Vec<Schedule>) {
+        self.entries.lock().unwrap().clear();
+        self.update(schedules);
+    }
+
+    /// 项目的定时运行修改后重新载入
+    ///
+    /// 没有修改的定时运行保留下一次触发的时间和运行状态，正在进行的依次运行继续；
+    /// 修改或删除的定时运行正在进行的依次运行在当前这次结束后不再继续
+    pub fn update(&self, schedules: Vec<Schedule>) {
+        let now = Local::now();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let mut previous = std::mem::take(&mut *entries);
+            for schedule in schedules {
+                let entry = match previous.remove(&schedule.name) {
+                    Some(entry) if entry.schedule == schedule => entry,
+                    _ => {
+                        let generation = self.generation.fetch_add(1, Ordering::SeqCst);
+                        Entry::new(schedule, now, generation)
+                    }
+                };
+                entries.insert(entry.schedule.name.clone(), entry);
+            }
+        }
+        self.ticker.call_once(|| {
+            thread::spawn(|| {
+                loop {
+                    thread::sleep(Duration::from_millis(TICK_MILLIS));
+                    App::with_scheduler(|scheduler| scheduler.tick());
+                }
+            });
+        });
+        emit_update();
+    }
+
+    pub fn list(&self) -> Vec<ScheduleInfo> {
+        let entries = self.entries.lock().unwrap();
+        entries.values().map(|entry| entry.info()).collect()
+    }
+
+    /// 立即触发指定的定时运行，不影响下一次触发的时间
+    pub fn trigger(&self, name: &str) -> Result<()> {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let Some(entry) = entries.get_mut(name) else {
+                return Err(anyhow!(t!("The schedule does not exist.", name = name)));
+            };
+            if let Some(error) = &entry.error {
+                return Err(anyhow!(error.clone()));
+            }
+            if entry.active {
+                return Err(anyhow!(t!("The schedule is already running.", name = name)));
+            }
+            self.fire(entry, Local::now());
+        }
+        emit_update();
+        Ok(())
+    }
+
+    fn tick(&self) {
+        let now = Local::now();
+        let mut fired = false;
+        {
+            let mut entries = self.entries.lock().unwrap();
+            for entry in entries.values_mut() {
+                if !entry.next.is_some_and(|next| next <= now) {
+                    continue;
+                }
+                // 已经过去的触发时间只算一次，之后从现在算起
+                entry.next = entry.schedule.next_after(now).unwrap_or(None);
+                fired = true;
+                if entry.active {
+                    let msg = t!(
+                        "The previous run of the schedule has not finished. Skipped.",
+                        name = &entry.schedule.name
+                    );
+                    Log::warn(msg.to_string()).send_to_app_log();
+                    continue;
+                }
+                self.fire(entry, now);
+            }
+        }
+        if fired {
+            emit_update();
+        }
+    }
+
+    fn fire(&self, entry: &mut Entry, now: DateTime<Local>) {
+        entry.active = true;
+        entry.last = Some(now);
+        let schedule = entry.schedule.clone();
+        let generation = entry.generation;
+        thread::spawn(move || {
+            run_sequence(&schedule, generation);
+            App::with_scheduler(|scheduler| scheduler.set_idle(&schedule.name, generation));
+            emit_update();
+        });
+    }
+
+    fn is_current(&self, name: &str, generation: u64) -> bool {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(name)
+            .is_some_and(|entry| entry.generation == generation)
+    }
+
+    fn set_running(&self, name: &str, generation: u64, run_id: RunId, iteration: u32) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries
+            .get_mut(name)
+            .filter(|entry| entry.generation == generation)
+        {
+            entry.run_id = Some(run_id);
+            entry.iteration = iteration;
+        }
+    }
+
+    fn set_idle(&self, name: &str, generation: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries
+            .get_mut(name)
+            .filter(|entry| entry.generation == generation)
+        {
+            entry.active = false;
+            entry.run_id = None;
+            entry.iteration = 0;
+        }
+    }
+}
+
+// 在当前线程中依次运行，有一次被停止或无法启动时不再继续
+fn run_sequence(schedule: &Schedule, generation: u64) {
+    let msg = t!("The schedule has started.", name = &schedule.name);
+    Log::info(msg.to_string()).send_to_app_log();
+    for iteration in 1..=schedule.repeat {
+        if !App::with_scheduler(|scheduler| scheduler.is_current(&schedule.name, generation)) {
+            return;
+        }
+        if iteration > 1 && schedule.repeat_delay_secs > 0 {
+            thread::sleep(Duration::from_secs(schedule.repeat_delay_secs));
+            if !App::with_scheduler(|scheduler| scheduler.is_current(&schedule.name, generation)) {
+                return;
+            }
+        }
+        let id = match start_run(schedule) {
+            Ok(id) => id,
+            Err(error) => {
+                Log::error(error.to_string()).send_to_app_log();
+                return;
+            }
+        };
+        App::with_scheduler(|scheduler| {
+            scheduler.set_running(&schedule.name, generation, id, iteration)
+        });
+        emit_update();
+        if wait_for_run(id) == Some(RunStatus::Stopped) {
+            let msg = t!("The schedule has been stopped.", name = &schedule.name);
+            Log::warn(msg.to_string()).send_to_app_log();
+            return;
+        }
+    }
+}
+
+fn start_run(schedule: &Schedule) -> Result<RunId> {
+    let profile = schedule.profile.as_deref();
+    let (options, watchdog) = App::try_with_project(|project| -> Result<_> {
+        Ok((
+            project.run_options(profile)?,
+            project.run_watchdog(profile)?,
+        ))
+    })??;
+    run::start(RunRequest {
+        path: schedule.path.clone(),
+        profile: schedule.profile.clone(),
+        options,
+        watchdog,
+    })
+}
+
+// 等待运行结束，返回结束时的状态
+fn wait_for_run(id: RunId) -> Option<RunStatus> {
+    loop {
+        let info = App::with_runs(|runs| runs.get(id))?;
+        if !info.status.is_active() {
+            return Some(info.status);
+        }
+        thread::sleep(Duration::from_millis(WAIT_POLL_MILLIS));
+    }
+}
+
+fn emit_update() {
+    let schedules = App::with_scheduler(|scheduler| scheduler.list());
+    emit(&App::get_app_handle(), "schedule:update", schedules);
+}
+
+fn timestamp(time: DateTime<Local>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
@@ -6,6 +6,7 @@ use rust_embed::Embed;
 #[include = "paddle_ocr/*"]
 // #[include = "app/config.toml"]
 #[include = "python/packages/kiwi/pyproject.toml"]
+#[include = "javascript/packages/kiwi/package.json"]
 // #[include = "editor/vscode_macos.zip"]
 // #[include = "python/interpreter_macos.zip"]
 // #[include = "python/wheels.zip"]
@@ -18,6 +19,7 @@ pub struct Asset;
 #[include = "paddle_ocr/*"]
 // #[include = "app/config.toml"]
 #[include = "python/packages/kiwi/pyproject.toml"]
+#[include = "javascript/packages/kiwi/package.json"]
 // #[include = "editor/vscode_windows.zip"]
 // #[include = "python/interpreter_windows.zip"]
 // #[include = "python/wheels.zip"]
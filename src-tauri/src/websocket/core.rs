@@ -1,17 +1,46 @@
 // done
 use super::message::{error_message, ok_message, probe_request, probe_response};
-use crate::commands::websocket;
+use crate::{commands::websocket, run::RunId};
 use anyhow::{Error, Result, anyhow};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
-use std::sync::OnceLock;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex, OnceLock},
+    time::Instant,
+};
 use tokio::net::{TcpListener, TcpStream};
-use tokio_tungstenite::{accept_async, client_async, tungstenite::Message};
+use tokio_tungstenite::{
+    accept_hdr_async, client_async,
+    tungstenite::{
+        Message,
+        handshake::server::{ErrorResponse, Request, Response},
+    },
+};
 use tokio_util::sync::CancellationToken;
 
-type Handler = fn(Value) -> Result<Value>;
+pub type Handler = fn(Value) -> Result<Value>;
 static CANCEL_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+/// 每次运行最近一次调用 Kiwi 方法的时间，用于检测脚本是否没有响应
+static LAST_CALLS: LazyLock<Mutex<HashMap<RunId, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 const IP: &str = "127.0.0.1";
+/// 脚本连接时在查询参数中带上运行编号，例如 `ws://127.0.0.1:9927/?run=1`
+const RUN_QUERY_KEY: &str = "run";
+
+/// 记录脚本调用了 Kiwi 的方法，通过 websocket 和在 Kiwi 进程内运行的脚本都会记录
+pub fn record_call(run_id: RunId) {
+    LAST_CALLS.lock().unwrap().insert(run_id, Instant::now());
+}
+
+pub fn last_call(run_id: RunId) -> Option<Instant> {
+    LAST_CALLS.lock().unwrap().get(&run_id).copied()
+}
+
+/// 运行结束后调用，不再保留它的调用时间
+pub fn forget_calls(run_id: RunId) {
+    LAST_CALLS.lock().unwrap().remove(&run_id);
+}
 
 pub fn shutdown() {
     if let Some(token) = CANCEL_TOKEN.get() {
@@ -96,7 +125,8 @@ pub async fn is_alive(port: u16) -> Result<bool> {
     Ok(false)
 }
 
-fn get_method_handler(method: &str) -> Option<Handler> {
+/// 按方法名查找处理函数，在 Kiwi 进程内运行的脚本也通过它直接调用
+pub fn get_method_handler(method: &str) -> Option<Handler> {
     use websocket::{frame, input};
     match method {
         "health_check" => Some(|_| Ok(probe_response())),
@@ -108,25 +138,59 @@ fn get_method_handler(method: &str) -> Option<Handler> {
         "save_frame" => Some(frame::save),
         "click_left" => Some(input::click_left),
         "click_right" => Some(input::click_right),
+        "click_middle" => Some(input::click_middle),
+        "double_click" => Some(input::double_click),
+        "click_at" => Some(input::click_at),
+        "drag" => Some(input::drag),
         "press_left" => Some(input::press_left),
         "press_right" => Some(input::press_right),
+        "press_middle" => Some(input::press_middle),
         "release_left" => Some(input::release_left),
         "release_right" => Some(input::release_right),
+        "release_middle" => Some(input::release_middle),
         "move_absolute" => Some(input::move_absolute),
+        "move_to" => Some(input::move_to),
         "move_relative" => Some(input::move_relative),
         "get_mouse_location" => Some(input::get_mouse_location),
+        "get_mouse_buttons" => Some(input::get_mouse_buttons),
+        "get_pressed_keys" => Some(input::get_pressed_keys),
+        "is_key_pressed" => Some(input::is_key_pressed),
+        "get_lock_keys" => Some(input::get_lock_keys),
         "scroll_vertical" => Some(input::scroll_vertical),
         "scroll_horizontal" => Some(input::scroll_horizontal),
         "press_key" => Some(input::press_key),
         "release_key" => Some(input::release_key),
         "click_key" => Some(input::click_key),
+        "hotkey" => Some(input::hotkey),
         "input_text" => Some(input::input_text),
+        "get_clipboard_text" => Some(input::get_clipboard_text),
+        "set_clipboard_text" => Some(input::set_clipboard_text),
+        "get_clipboard_image" => Some(input::get_clipboard_image),
+        "set_input_mode" => Some(input::set_input_mode),
+        "get_input_mode" => Some(input::get_input_mode),
+        "set_input_dry_run" => Some(input::set_input_dry_run),
+        "get_input_log" => Some(input::get_input_log),
+        "clear_input_log" => Some(input::clear_input_log),
+        "set_input_region" => Some(input::set_input_region),
+        "clear_input_region" => Some(input::clear_input_region),
+        "get_input_region" => Some(input::get_input_region),
+        "set_input_humanize" => Some(input::set_input_humanize),
+        "clear_input_humanize" => Some(input::clear_input_humanize),
+        "get_input_humanize" => Some(input::get_input_humanize),
         _ => None,
     }
 }
 
 async fn accept_connection(stream: TcpStream) -> Result<()> {
-    let ws_stream = accept_async(stream).await?;
+    let mut run_id = None;
+    let ws_stream = accept_hdr_async(
+        stream,
+        |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            run_id = request.uri().query().and_then(parse_run_id);
+            Ok(response)
+        },
+    )
+    .await?;
     let (mut write, mut read) = ws_stream.split();
 
     while let Some(Ok(msg)) = read.next().await {
@@ -152,11 +216,25 @@ async fn accept_connection(stream: TcpStream) -> Result<()> {
 
                 let args = value.get("args").cloned().unwrap_or(Value::Null);
 
+                // Kiwi 自己检查服务是否可用时不算作脚本的调用，
+                // 没有带上运行编号的连接（例如在 Kiwi 之外启动的脚本）不记录
+                if method != "health_check" {
+                    if let Some(run_id) = run_id {
+                        record_call(run_id);
+                    }
+                }
                 match get_method_handler(method) {
-                    Some(handler) => match handler(args) {
-                        Ok(result) => write.send(ok_message(result)).await?,
-                        Err(e) => write.send(error_message(e.to_string())).await?,
-                    },
+                    Some(handler) => {
+                        // 输入方法会等待移动和拖动执行完成，查找图片也比较耗时，
+                        // 在阻塞线程中执行，避免占住 tokio 的工作线程影响其他连接
+                        let result = tokio::task::spawn_blocking(move || handler(args))
+                            .await
+                            .unwrap_or_else(|e| Err(anyhow!(e)));
+                        match result {
+                            Ok(result) => write.send(ok_message(result)).await?,
+                            Err(e) => write.send(error_message(e.to_string())).await?,
+                        }
+                    }
                     None => {
                         write
                             .send(error_message(format!("Unsupported method '{}'", method)))
@@ -176,3 +254,14 @@ async fn accept_connection(stream: TcpStream) -> Result<()> {
 
     Ok(())
 }
+
+// 从查询参数中读取运行编号，脚本从环境变量 `KIWI_RUN_ID` 得到它
+fn parse_run_id(query: &str) -> Option<RunId> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        if key != RUN_QUERY_KEY {
+            return None;
+        }
+        value.parse().ok()
+    })
+}
@@ -27,8 +27,17 @@ interface ConfigApp {
   locale: Locale;
 }
 
+interface ConfigRecorder {
+  start: string;
+  stop: string;
+  pause: string;
+  resume: string;
+  marker: string;
+}
+
 interface Config {
   app: ConfigApp;
+  recorder?: ConfigRecorder;
 }
 
 interface App {
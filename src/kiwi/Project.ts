@@ -1,5 +1,13 @@
 import { invoke } from "@tauri-apps/api/core";
-import { Base64Png, EmitProject } from "@types";
+import {
+  Base64Png,
+  EmitProject,
+  RunInfo,
+  RunLogLine,
+  RunOptions,
+  Schedule,
+  ScheduleInfo,
+} from "@types";
 import { msgError } from "@utils/msg";
 
 type VerifyStatus = "valid" | "invalid" | "moved";
@@ -27,6 +35,10 @@ class ProjectModel {
     return this.project.kiwiVersion;
   }
 
+  get runProfiles() {
+    return this.project.runProfiles;
+  }
+
   get mainFileFullPath() {
     return this.project.mainFileFullPath;
   }
@@ -113,10 +125,76 @@ class ProjectModel {
     }
   }
 
-  async runScript(path: string): Promise<void> {
+  async runScript(path: string, options: RunOptions = {}): Promise<number> {
+    this.ensureExists();
+    try {
+      return await invoke("run_script", { path, ...options });
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async stopScript(runId: number): Promise<void> {
+    try {
+      return await invoke("stop_script", { runId });
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async listRuns(): Promise<RunInfo[]> {
+    try {
+      return await invoke("list_runs");
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async getRunLog(runId: number): Promise<RunLogLine[]> {
+    try {
+      return await invoke("get_run_log", { runId });
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async listSchedules(): Promise<ScheduleInfo[]> {
+    try {
+      return await invoke("list_schedules");
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async saveSchedule(schedule: Schedule): Promise<void> {
+    this.ensureExists();
+    try {
+      return await invoke("save_schedule", { schedule });
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async removeSchedule(name: string): Promise<void> {
+    this.ensureExists();
+    try {
+      return await invoke("remove_schedule", { name });
+    } catch (e: unknown) {
+      msgError(e);
+      throw e;
+    }
+  }
+
+  async runSchedule(name: string): Promise<void> {
     this.ensureExists();
     try {
-      return await invoke("run_script", { path });
+      return await invoke("run_schedule", { name });
     } catch (e: unknown) {
       msgError(e);
       throw e;
@@ -6,6 +6,7 @@ interface EmitProject {
   mainFile: string | null;
   path: string | null;
   kiwiVersion: string | null;
+  runProfiles: string[];
   mainFileFullPath?: string;
 }
 
@@ -16,6 +17,7 @@ namespace EmitProject {
     mainFile: null,
     path: null,
     kiwiVersion: null,
+    runProfiles: [],
   });
 }
 
@@ -17,6 +17,9 @@ import { WeightPoint } from "./weight-point";
 import { WindowLabel } from "./window-label";
 import { Locale, AppLocale, locales } from "./locale";
 import { EmitMsg } from "./emit-msg";
+import { Watchdog, RunOptions } from "./run-options";
+import { RunStatus, RunInfo, RunLogLine } from "./run-info";
+import { Schedule, ScheduleInfo } from "./schedule";
 
 export {
   u8,
@@ -43,4 +46,11 @@ export type {
   EmitLog,
   EmitProgress,
   EmitMsg,
+  Watchdog,
+  RunOptions,
+  RunStatus,
+  RunInfo,
+  RunLogLine,
+  Schedule,
+  ScheduleInfo,
 };
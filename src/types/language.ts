@@ -1,3 +1,3 @@
-type Language = "python" | "lua";
+type Language = "python" | "javascript" | "lua" | "rhai";
 
 export type { Language };
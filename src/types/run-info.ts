@@ -0,0 +1,29 @@
+type RunStatus = "starting" | "running" | "restarting" | "finished" | "failed" | "stopped";
+
+/**
+ * 一次脚本运行，`run:update` 事件的内容
+ */
+type RunInfo = {
+  id: number;
+  path: string;
+  profile: string | null;
+  pid: number;
+  status: RunStatus;
+  attempt: number;
+  exit: string | null;
+  reason: string | null;
+  startedAt: number;
+  endedAt: number | null;
+};
+
+/**
+ * 脚本输出的一行，`run:log` 事件的内容
+ */
+type RunLogLine = {
+  runId: number;
+  stream: "stdout" | "stderr";
+  line: string;
+  time: number;
+};
+
+export type { RunStatus, RunInfo, RunLogLine };
@@ -0,0 +1,23 @@
+/**
+ * 运行监控，字段与项目配置中的 `[run.watchdog]` 相同，`0` 表示不限制
+ */
+type Watchdog = {
+  max_runtime_secs?: number;
+  inactivity_secs?: number;
+  max_restarts?: number;
+  backoff_secs?: number;
+  max_backoff_secs?: number;
+};
+
+/**
+ * 运行脚本的选项，未设置的项使用运行配置（profile）中的值
+ */
+type RunOptions = {
+  profile?: string;
+  args?: string[];
+  env?: Record<string, string>;
+  cwd?: string;
+  watchdog?: Watchdog;
+};
+
+export type { Watchdog, RunOptions };
@@ -0,0 +1,30 @@
+/**
+ * 定时运行，字段与项目配置中的 `[[schedules]]` 相同
+ */
+type Schedule = {
+  name: string;
+  path: string;
+  profile?: string | null;
+  enabled?: boolean;
+  repeat?: number;
+  repeat_delay_secs?: number;
+} & (
+  | { trigger: "at"; at: string }
+  | { trigger: "cron"; cron: string }
+  | { trigger: "interval"; interval_secs: number }
+  | { trigger: "manual" }
+);
+
+/**
+ * 定时运行的状态，`schedule:update` 事件的内容为它的数组
+ */
+type ScheduleInfo = {
+  schedule: Schedule;
+  nextRunAt: number | null;
+  lastRunAt: number | null;
+  runId: number | null;
+  iteration: number;
+  error: string | null;
+};
+
+export type { Schedule, ScheduleInfo };